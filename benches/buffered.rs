@@ -0,0 +1,79 @@
+//! Compares the number of calls to `poll_read` and the time needed to read netstrings from an
+//! unbuffered reader with [AsyncNetstringRead] and from a `BufReader` with
+//! [AsyncNetstringBufRead]. Run it with `cargo bench --bench buffered`.
+
+use std::io::Result;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, BufReader, ReadBuf};
+use tokio_netstring_trait::{AsyncNetstringBufRead, AsyncNetstringRead, NetstringWrite};
+
+const MESSAGES: usize = 10_000;
+
+/// Counts the calls to `poll_read`, each of which is a syscall on an unbuffered socket.
+struct CountingReader<'a> {
+    data: &'a [u8],
+    reads: usize,
+}
+
+impl AsyncRead for CountingReader<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        self.reads += 1;
+        Pin::new(&mut self.data).poll_read(cx, buf)
+    }
+}
+
+fn messages(len: usize) -> Vec<u8> {
+    let payload = vec![b'x'; len];
+    let mut data = Vec::new();
+    for _ in 0..MESSAGES {
+        data.write_netstring(&payload).unwrap();
+    }
+    data
+}
+
+fn unbuffered(data: &[u8]) -> (usize, Duration) {
+    let mut reader = CountingReader { data, reads: 0 };
+    let mut buf = vec![0; 4096];
+    let start = Instant::now();
+    tokio_test::block_on(async {
+        for _ in 0..MESSAGES {
+            reader.read_netstring(&mut buf).await.unwrap();
+        }
+    });
+    (reader.reads, start.elapsed())
+}
+
+fn buffered(data: &[u8]) -> (usize, Duration) {
+    let mut reader = BufReader::new(CountingReader { data, reads: 0 });
+    let mut buf = vec![0; 4096];
+    let start = Instant::now();
+    tokio_test::block_on(async {
+        for _ in 0..MESSAGES {
+            reader.read_netstring_buffered(&mut buf).await.unwrap();
+        }
+    });
+    (reader.get_ref().reads, start.elapsed())
+}
+
+fn main() {
+    println!(
+        "{:>8} {:>12} {:>12} {:>12} {:>12}",
+        "payload", "reads", "time", "buf reads", "buf time"
+    );
+    for len in [0, 16, 256, 4096] {
+        let data = messages(len);
+        let (reads, time) = unbuffered(&data);
+        let (buf_reads, buf_time) = buffered(&data);
+        println!(
+            "{:>8} {:>12} {:>12?} {:>12} {:>12?}",
+            len, reads, time, buf_reads, buf_time
+        );
+    }
+}
@@ -0,0 +1,561 @@
+use std::fs::File;
+use std::io::{ErrorKind, IoSlice, Read, Result, Write};
+use std::ops::Deref;
+
+use crate::decoder::{DecodeEvent, NetstringDecoder};
+use crate::encoder::{self, MAX_HEADER_LENGTH};
+use crate::frame::{self, Frame, ReadEvent, COALESCE_LIMIT};
+use crate::instrument::{trace_frame, trace_read_frame, Direction};
+use crate::logging;
+use crate::options::{NetstringReadOptions, OversizePolicy};
+
+/// The `NetstringRead` trait is the blocking counterpart of
+/// [AsyncNetstringRead](crate::AsyncNetstringRead) for any stream that implements
+/// `std::io::Read`. It returns the same errors with the same error kinds.
+pub trait NetstringRead: Read {
+    /// This method allows to read one netstring into the buffer given. It is advised to use
+    /// this Trait on a [std::io::BufReader] to avoid repeated system calls during parsing.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::NetstringRead;
+    ///
+    /// let buf = [0; 1024];
+    /// let len: usize = stream.read_netstring(&mut buf).unwrap();
+    /// let buf: &[u8] = &buf[..len];
+    /// ```
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring](crate::AsyncNetstringRead::read_netstring).
+    fn read_netstring(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        self.read_netstring_with(buffer, NetstringReadOptions::default())
+    }
+
+    /// This method works like [NetstringRead::read_netstring], but applies the limits set in
+    /// `options`.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring_with](crate::AsyncNetstringRead::read_netstring_with).
+    fn read_netstring_with(
+        &mut self,
+        buffer: &mut [u8],
+        options: NetstringReadOptions,
+    ) -> Result<usize> {
+        read_message(
+            self,
+            buffer,
+            &mut NetstringDecoder::with_options(options),
+            options.oversize,
+        )
+    }
+
+    /// This method continues to read a netstring of length `len`, after
+    /// [NetstringRead::read_netstring] failed with a [BufferTooSmall](crate::BufferTooSmall)
+    /// error.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::resume_netstring](crate::AsyncNetstringRead::resume_netstring).
+    fn resume_netstring(&mut self, buffer: &mut [u8], len: usize) -> Result<usize> {
+        read_message(
+            self,
+            buffer,
+            &mut NetstringDecoder::resume(len, NetstringReadOptions::default()),
+            OversizePolicy::Error,
+        )
+    }
+
+    /// This method allows to read one netstring. It returns the netstring as a `Vec<u8>` and
+    /// allocates the memory itself, therefore avoiding a to small buffer.
+    ///
+    /// While this may be use full during development, it should be avoided in production, since it
+    /// can allocate memory and a DDOS attack is therefore easily possible.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring_alloc](crate::AsyncNetstringRead::read_netstring_alloc).
+    fn read_netstring_alloc(&mut self) -> Result<Vec<u8>> {
+        self.read_netstring_alloc_with(NetstringReadOptions::default())
+    }
+
+    /// This method works like [NetstringRead::read_netstring_alloc], but applies the limits set
+    /// in `options`.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring_alloc_with](crate::AsyncNetstringRead::read_netstring_alloc_with).
+    fn read_netstring_alloc_with(&mut self, options: NetstringReadOptions) -> Result<Vec<u8>> {
+        read_message_alloc(self, &mut NetstringDecoder::with_options(options))
+    }
+
+    /// This method reads one netstring, whose payload nests netstrings, and returns the nested
+    /// payloads.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring_list](crate::AsyncNetstringRead::read_netstring_list).
+    fn read_netstring_list(&mut self) -> Result<Vec<Vec<u8>>> {
+        self.read_netstring_list_with(NetstringReadOptions::default())
+    }
+
+    /// This method works like [NetstringRead::read_netstring_list], but applies the limits set
+    /// in `options` to the outer netstring.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring_list_with](crate::AsyncNetstringRead::read_netstring_list_with).
+    fn read_netstring_list_with(&mut self, options: NetstringReadOptions) -> Result<Vec<Vec<u8>>> {
+        frame::decode_list(&self.read_netstring_alloc_with(options)?)
+    }
+
+    /// This method reads one netstring and deserializes a value from it like
+    /// [from_netstring](crate::from_netstring).
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring_serde](crate::AsyncNetstringRead::read_netstring_serde).
+    #[cfg(feature = "serde")]
+    fn read_netstring_serde<T>(&mut self) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.read_netstring_serde_with(NetstringReadOptions::default())
+    }
+
+    /// This method works like [NetstringRead::read_netstring_serde], but applies the limits set
+    /// in `options` to the outer netstring.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::read_netstring_serde_with](crate::AsyncNetstringRead::read_netstring_serde_with).
+    #[cfg(feature = "serde")]
+    fn read_netstring_serde_with<T>(&mut self, options: NetstringReadOptions) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let payload = self.read_netstring_alloc_with(options)?;
+        Ok(crate::de::from_payload(&payload)?)
+    }
+
+    /// This method works like [NetstringRead::read_netstring], but returns `None` if the stream
+    /// ends before the first byte of the length header.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::try_read_netstring](crate::AsyncNetstringRead::try_read_netstring).
+    fn try_read_netstring(&mut self, buffer: &mut [u8]) -> Result<Option<usize>> {
+        self.try_read_netstring_with(buffer, NetstringReadOptions::default())
+    }
+
+    /// This method works like [NetstringRead::try_read_netstring], but applies the limits set
+    /// in `options`.
+    fn try_read_netstring_with(
+        &mut self,
+        buffer: &mut [u8],
+        options: NetstringReadOptions,
+    ) -> Result<Option<usize>> {
+        let mut decoder = NetstringDecoder::with_options(options);
+        let res = read_message(self, buffer, &mut decoder, options.oversize);
+        eof_at_start(res, &decoder)
+    }
+
+    /// This method works like [NetstringRead::read_netstring_alloc], but returns `None` if the
+    /// stream ends before the first byte of the length header.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::try_read_netstring_alloc](crate::AsyncNetstringRead::try_read_netstring_alloc).
+    fn try_read_netstring_alloc(&mut self) -> Result<Option<Vec<u8>>> {
+        self.try_read_netstring_alloc_with(NetstringReadOptions::default())
+    }
+
+    /// This method works like [NetstringRead::try_read_netstring_alloc], but applies the limits
+    /// set in `options`.
+    fn try_read_netstring_alloc_with(
+        &mut self,
+        options: NetstringReadOptions,
+    ) -> Result<Option<Vec<u8>>> {
+        let mut decoder = NetstringDecoder::with_options(options);
+        let res = read_message_alloc(self, &mut decoder);
+        eof_at_start(res, &decoder)
+    }
+
+    /// This method reads one netstring and discards its content.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringRead::drop_netstring](crate::AsyncNetstringRead::drop_netstring).
+    fn drop_netstring(&mut self) -> Result<()> {
+        trace_read_frame(
+            Direction::Drop,
+            |len| *len,
+            &mut NetstringDecoder::new(),
+            |decoder| drop_message(self, decoder),
+        )
+        .map(drop)
+    }
+}
+
+impl<Reader: Read + ?Sized> NetstringRead for Reader {}
+
+/// The `NetstringWrite` trait is the blocking counterpart of
+/// [AsyncNetstringWrite](crate::AsyncNetstringWrite) for any stream that implements
+/// `std::io::Write`.
+pub trait NetstringWrite: Write {
+    /// Write the slice as a netstring to the stream and flush it. Small payloads are written
+    /// together with the header and the terminator in one call, large ones with vectored writes
+    /// to avoid copying them.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::NetstringWrite;
+    ///
+    /// let msg = "Hello, World!";
+    /// stream.write_netstring(&msg.as_bytes()).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring](crate::AsyncNetstringWrite::write_netstring).
+    fn write_netstring(&mut self, data: &[u8]) -> Result<()> {
+        trace_frame(
+            Direction::Write,
+            |()| data.len(),
+            || {
+                write_frame(self, &Frame::new(&[data]))?;
+                self.flush()
+            },
+        )
+        .map(|()| logging::log_netstring(logging::WRITING, data))
+    }
+
+    /// This method works like [NetstringWrite::write_netstring], but does not flush the stream
+    /// afterwards.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring_no_flush](crate::AsyncNetstringWrite::write_netstring_no_flush).
+    fn write_netstring_no_flush(&mut self, data: &[u8]) -> Result<()> {
+        trace_frame(
+            Direction::Write,
+            |()| data.len(),
+            || write_frame(self, &Frame::new(&[data])),
+        )
+        .map(|()| logging::log_netstring(logging::WRITING, data))
+    }
+
+    /// This method writes one netstring, whose payload consists of all `parts` in order, and
+    /// flushes the stream afterwards.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring_parts](crate::AsyncNetstringWrite::write_netstring_parts).
+    fn write_netstring_parts(&mut self, parts: &[&[u8]]) -> Result<()> {
+        let frame = Frame::new(parts);
+        trace_frame(
+            Direction::Write,
+            |()| frame.data_len(),
+            || {
+                write_frame(self, &frame)?;
+                self.flush()
+            },
+        )
+        .map(|()| logging::log_netstring_parts(logging::WRITING, parts))
+    }
+
+    /// This method writes one netstring, whose payload is the concatenation of every item of
+    /// `items` framed as a netstring, and flushes the stream afterwards.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring_list](crate::AsyncNetstringWrite::write_netstring_list).
+    fn write_netstring_list(&mut self, items: &[&[u8]]) -> Result<()> {
+        let mut buf = Vec::new();
+        crate::list::encode_list_to_vec(items, &mut buf);
+
+        write_encoded(self, &buf).map(|()| logging::log_encoded(logging::WRITING, &buf))
+    }
+
+    /// This method serializes `value` like [to_netstring](crate::to_netstring), writes it and
+    /// flushes the stream afterwards.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring_serde](crate::AsyncNetstringWrite::write_netstring_serde).
+    #[cfg(feature = "serde")]
+    fn write_netstring_serde<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let buf = crate::ser::to_netstring(value)?;
+
+        write_encoded(self, &buf).map(|()| logging::log_encoded(logging::WRITING, &buf))
+    }
+
+    /// This method works like [NetstringWrite::write_netstring_parts], but takes the parts as
+    /// `IoSlice`s.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring_vectored](crate::AsyncNetstringWrite::write_netstring_vectored).
+    fn write_netstring_vectored(&mut self, parts: &[IoSlice<'_>]) -> Result<()> {
+        let frame = Frame::new(parts);
+        trace_frame(
+            Direction::Write,
+            |()| frame.data_len(),
+            || {
+                write_frame(self, &frame)?;
+                self.flush()
+            },
+        )
+        .map(|()| logging::log_netstring_parts(logging::WRITING, parts))
+    }
+
+    /// This method writes one netstring of length `len`, whose payload is copied from `reader`,
+    /// and flushes the stream afterwards.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring_from_reader](crate::AsyncNetstringWrite::write_netstring_from_reader).
+    fn write_netstring_from_reader<R>(&mut self, len: usize, reader: &mut R) -> Result<()>
+    where
+        R: Read + ?Sized,
+    {
+        trace_frame(
+            Direction::Write,
+            |()| len,
+            || write_from_reader(self, len, reader),
+        )
+        .map(|()| logging::log_netstring_len(logging::WRITING, len))
+    }
+
+    /// This method writes the content of `file` as one netstring, with the length taken from
+    /// the metadata of the file.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstring_from_file](crate::AsyncNetstringWrite::write_netstring_from_file).
+    fn write_netstring_from_file(&mut self, file: &mut File) -> Result<()> {
+        let len = frame::file_len(file.metadata()?.len())?;
+        self.write_netstring_from_reader(len, file)
+    }
+
+    /// This method writes every item of `netstrings` as a netstring to the stream and flushes
+    /// it once after all of them are written.
+    ///
+    /// # Errors
+    /// See [AsyncNetstringWrite::write_netstrings](crate::AsyncNetstringWrite::write_netstrings).
+    fn write_netstrings<I>(&mut self, netstrings: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        for data in netstrings {
+            self.write_netstring_no_flush(data.as_ref())?;
+        }
+        self.flush()
+    }
+}
+
+impl<Writer: Write + ?Sized> NetstringWrite for Writer {}
+
+fn write_from_reader<W, R>(writer: &mut W, len: usize, reader: &mut R) -> Result<()>
+where
+    W: Write + ?Sized,
+    R: Read + ?Sized,
+{
+    let mut header = [0; MAX_HEADER_LENGTH];
+    writer.write_all(encoder::encode_header(len, &mut header))?;
+
+    let copied = std::io::copy(&mut Read::take(&mut *reader, len as u64), writer)?;
+    if copied < len as u64 {
+        return Err(frame::source_too_short(copied as usize, len));
+    }
+
+    //make sure the reader does not hold more than announced
+    let mut byte = [0; 1];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => break,
+            Ok(_) => return Err(frame::source_too_long(len)),
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    writer.write_all(b",")?;
+    writer.flush()
+}
+
+/// Writes a netstring encoded up front and flushes the writer afterwards.
+fn write_encoded<W: Write + ?Sized>(writer: &mut W, netstring: &[u8]) -> Result<()> {
+    let len = frame::payload_len(netstring);
+    trace_frame(
+        Direction::Write,
+        |()| len,
+        || {
+            writer.write_all(netstring)?;
+            writer.flush()
+        },
+    )
+}
+
+/// Writes small netstrings with one call and large ones with vectored writes.
+fn write_frame<W, T>(writer: &mut W, frame: &Frame<'_, T>) -> Result<()>
+where
+    W: Write + ?Sized,
+    T: Deref<Target = [u8]>,
+{
+    if frame.data_len() <= COALESCE_LIMIT {
+        return writer.write_all(&frame.to_vec());
+    }
+
+    let mut prog = 0;
+    while prog < frame.encoded_len() {
+        let (slices, len) = frame.remaining(prog);
+        match writer.write_vectored(&slices[..len]) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(wrote) => prog += wrote,
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+fn read_byte<R: Read + ?Sized>(reader: &mut R, decoder: &NetstringDecoder) -> Result<u8> {
+    let mut byte = [0; 1];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => return Err(frame::eof(decoder)),
+            Ok(_) => return Ok(byte[0]),
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn read_message<R: Read + ?Sized>(
+    reader: &mut R,
+    buffer: &mut [u8],
+    decoder: &mut NetstringDecoder,
+    oversize: OversizePolicy,
+) -> Result<usize> {
+    trace_read_frame(
+        Direction::Read,
+        |len| *len,
+        decoder,
+        |decoder| read_frame(reader, buffer, decoder, oversize),
+    )
+}
+
+fn read_frame<R: Read + ?Sized>(
+    reader: &mut R,
+    buffer: &mut [u8],
+    decoder: &mut NetstringDecoder,
+    oversize: OversizePolicy,
+) -> Result<usize> {
+    let mut filled = 0;
+
+    loop {
+        //verify that the message fits into the buffer
+        let remaining = decoder.body_remaining();
+        if remaining > buffer.len() - filled {
+            let len = filled + remaining;
+            match oversize {
+                OversizePolicy::Error => return Err(frame::buffer_to_small(len)),
+                OversizePolicy::Fail => return Err(frame::buffer_to_small_fatal(len)),
+                OversizePolicy::Truncate if filled < buffer.len() => {}
+                OversizePolicy::Truncate => {
+                    logging::log_netstring_len(logging::DROPPING, len);
+                    drop_message(reader, decoder)?;
+                    return Ok(len);
+                }
+                OversizePolicy::Skip => {
+                    logging::log_netstring_len(logging::DROPPING, len);
+                    drop_message(reader, decoder)?;
+                    return Err(frame::buffer_to_small(len));
+                }
+            }
+        }
+
+        match read_event(reader, decoder, &mut buffer[filled..])? {
+            ReadEvent::Length(_) => {}
+            ReadEvent::Body(read) => filled += read,
+            ReadEvent::Complete => break,
+        }
+    }
+
+    logging::log_netstring(logging::READING, &buffer[..filled]);
+
+    Ok(filled)
+}
+
+fn read_message_alloc<R: Read + ?Sized>(
+    reader: &mut R,
+    decoder: &mut NetstringDecoder,
+) -> Result<Vec<u8>> {
+    trace_read_frame(Direction::Read, Vec::len, decoder, |decoder| {
+        read_frame_alloc(reader, decoder)
+    })
+}
+
+fn read_frame_alloc<R: Read + ?Sized>(
+    reader: &mut R,
+    decoder: &mut NetstringDecoder,
+) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut filled = 0;
+
+    loop {
+        if filled == buffer.len() && decoder.body_remaining() > 0 {
+            frame::grow_payload(&mut buffer, decoder.body_remaining())?;
+        }
+
+        match read_event(reader, decoder, &mut buffer[filled..])? {
+            ReadEvent::Length(_) => {}
+            ReadEvent::Body(read) => filled += read,
+            ReadEvent::Complete => {
+                logging::log_netstring(logging::READING, &buffer);
+                return Ok(buffer);
+            }
+        }
+    }
+}
+
+/// Turns an EOF before the first byte of a netstring into `None`.
+fn eof_at_start<T>(res: Result<T>, decoder: &NetstringDecoder) -> Result<Option<T>> {
+    match res {
+        Ok(val) => Ok(Some(val)),
+        Err(err) if frame::is_eof_at_boundary(&err, decoder) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// The blocking counterpart of `poll_read_event`.
+fn read_event<R: Read + ?Sized>(
+    reader: &mut R,
+    decoder: &mut NetstringDecoder,
+    body: &mut [u8],
+) -> Result<ReadEvent> {
+    loop {
+        let remaining = decoder.body_remaining();
+        if remaining > 0 {
+            let len = remaining.min(body.len());
+            let read = match reader.read(&mut body[..len]) {
+                Ok(0) => return Err(frame::eof(decoder)),
+                Ok(read) => read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            decoder.decode(&body[..read]);
+            return Ok(ReadEvent::Body(read));
+        }
+
+        let byte = read_byte(reader, decoder)?;
+        match decoder.decode(&[byte]).1 {
+            None => {}
+            Some(DecodeEvent::Length(len)) => return Ok(ReadEvent::Length(len)),
+            Some(DecodeEvent::Body(_)) => unreachable!("the payload is read into the buffer"),
+            Some(DecodeEvent::Complete) => return Ok(ReadEvent::Complete),
+            Some(DecodeEvent::Error(err)) => return Err(err.into()),
+        }
+    }
+}
+
+fn drop_message<R: Read + ?Sized>(reader: &mut R, decoder: &mut NetstringDecoder) -> Result<usize> {
+    let mut buf = [0; 1024];
+    let mut len = 0;
+    loop {
+        match read_event(reader, decoder, &mut buf)? {
+            ReadEvent::Length(n) => {
+                logging::log_netstring_len(logging::DROPPING, n);
+                len = n;
+            }
+            ReadEvent::Body(_) => {}
+            ReadEvent::Complete => return Ok(len),
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::future::Future;
+use std::marker::{PhantomPinned, Unpin};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, ReadBuf, Result};
+
+use crate::decoder::NetstringDecoder;
+use crate::frame::{ReadEvent, COPY_BUFFER_SIZE};
+use crate::instrument::{Direction, FrameTrace};
+use crate::logging;
+use crate::poll::poll_read_event;
+
+pub(crate) fn read_netstring_body<R>(reader: &mut R) -> ReadBody<'_, R>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    ReadBody {
+        reader: Some(reader),
+        decoder: NetstringDecoder::new(),
+        trace: Some(FrameTrace::start(Direction::Read)),
+        _pin: PhantomPinned,
+    }
+}
+
+pin_project! {
+    /// Creates a future which will read the length header of a netstring and return the payload
+    /// as a [NetstringBody], returning an error if EOF is hit sooner.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct ReadBody<'a, R: ?Sized> {
+        reader: Option<&'a mut R>,
+        decoder: NetstringDecoder,
+        trace: Option<FrameTrace>,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+fn poll_length<R>(
+    reader: &mut R,
+    decoder: &mut NetstringDecoder,
+    cx: &mut Context<'_>,
+) -> Poll<Result<usize>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    loop {
+        if let ReadEvent::Length(len) = ready_and_ok!(poll_read_event(reader, decoder, &mut [], cx))
+        {
+            return Poll::Ready(Ok(len));
+        }
+    }
+}
+
+impl<'a, R> Future for ReadBody<'a, R>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    type Output = Result<NetstringBody<'a, R>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<NetstringBody<'a, R>>> {
+        let me = self.project();
+        let trace = me.trace.take().expect("ReadBody polled after completion");
+        let reader = me
+            .reader
+            .as_mut()
+            .expect("ReadBody polled after completion");
+        let res = {
+            let _entered = trace.enter();
+            poll_length(&mut **reader, me.decoder, cx)
+        };
+
+        //the body finishes the trace, once the terminator has been read
+        let len = match res {
+            Poll::Ready(res) => {
+                if res.is_err() {
+                    trace.finish_read(&res, |len| *len, me.decoder);
+                }
+                res?
+            }
+            Poll::Pending => {
+                *me.trace = Some(trace);
+                return Poll::Pending;
+            }
+        };
+
+        logging::log_netstring_len(logging::READING, len);
+        Poll::Ready(Ok(NetstringBody {
+            reader: me.reader.take().expect("the reader is taken only once"),
+            decoder: me.decoder.clone(),
+            len,
+            done: false,
+            trace: Some(trace),
+        }))
+    }
+}
+
+pin_project! {
+    /// The payload of a netstring, returned by
+    /// [AsyncNetstringRead::read_netstring_body](crate::AsyncNetstringRead::read_netstring_body).
+    ///
+    /// It reads exactly the length announced by the header from the underlying reader and
+    /// reports EOF afterwards. Before reporting EOF, the terminator `b','` is read and verified,
+    /// so a netstring with an invalid terminator results in an error of kind
+    /// `ErrorKind::InvalidData`.
+    ///
+    /// Should the body be dropped before EOF has been reached, the underlying reader is left in
+    /// the middle of the netstring and is unusable afterwards: the next read parses the rest of
+    /// the payload as a length header, so it fails or, worse, returns a netstring the peer placed
+    /// inside the payload. Dropping the body only logs a warning, as the rest can't be discarded
+    /// without awaiting. Use [NetstringBody::finish] to discard the rest of the payload instead.
+    #[derive(Debug)]
+    pub struct NetstringBody<'a, R: ?Sized> {
+        reader: &'a mut R,
+        decoder: NetstringDecoder,
+        len: usize,
+        done: bool,
+        trace: Option<FrameTrace>,
+    }
+
+    impl<R: ?Sized> PinnedDrop for NetstringBody<'_, R> {
+        fn drop(this: Pin<&mut Self>) {
+            let me = this.project();
+            if !*me.done {
+                logging::log_body_dropped(me.decoder.body_remaining());
+            }
+        }
+    }
+}
+
+impl<'a, R> NetstringBody<'a, R>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    /// Returns the length of the payload announced by the header.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the payload is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes of the payload, that have not been read yet.
+    pub fn remaining(&self) -> usize {
+        self.decoder.body_remaining()
+    }
+
+    /// Discards the rest of the payload and verifies the terminator, leaving the underlying
+    /// reader at the start of the next netstring.
+    pub fn finish(self) -> FinishBody<'a, R> {
+        let scratch = vec![0; self.remaining().min(COPY_BUFFER_SIZE)].into_boxed_slice();
+        FinishBody {
+            body: self,
+            scratch,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+impl<R> AsyncRead for NetstringBody<'_, R>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let me = self.project();
+
+        if *me.done || (me.decoder.body_remaining() > 0 && buf.remaining() == 0) {
+            return Poll::Ready(Ok(()));
+        }
+
+        let body = buf.initialize_unfilled();
+        let res = {
+            let _entered = me.trace.as_ref().map(FrameTrace::enter);
+            ready!(poll_read_event(&mut **me.reader, me.decoder, body, cx))
+        };
+
+        //a body dropped early is not reported, as its outcome is unknown
+        match res {
+            Ok(ReadEvent::Body(read)) => buf.advance(read),
+            Ok(ReadEvent::Complete) => {
+                *me.done = true;
+                if let Some(trace) = me.trace.take() {
+                    trace.report(Ok(*me.len), me.decoder.frame_bytes());
+                }
+            }
+            Ok(ReadEvent::Length(_)) => unreachable!("the header has been read already"),
+            Err(err) => {
+                if let Some(trace) = me.trace.take() {
+                    trace.report(Err(&err), me.decoder.frame_bytes());
+                }
+                return Poll::Ready(Err(err));
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+pin_project! {
+    /// Creates a future which will discard the rest of a [NetstringBody] and verify its
+    /// terminator.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct FinishBody<'a, R: ?Sized> {
+        #[pin]
+        body: NetstringBody<'a, R>,
+        scratch: Box<[u8]>,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+impl<R> Future for FinishBody<'_, R>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut me = self.project();
+
+        while !me.body.done {
+            let mut buf = ReadBuf::new(&mut me.scratch[..]);
+            ready_and_ok!(me.body.as_mut().poll_read(cx, &mut buf));
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,218 @@
+use std::io::{Error, ErrorKind, Result};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::decoder::{DecodeEvent, NetstringDecoder};
+use crate::encoder::{self, MAX_HEADER_LENGTH};
+use crate::error::NetstringError;
+use crate::frame::{self, COPY_BUFFER_SIZE};
+use crate::instrument::{self, Direction, FrameTrace};
+use crate::logging;
+use crate::options::NetstringReadOptions;
+
+/// A [Decoder] and [Encoder] for netstrings, to be used with `tokio_util::codec::Framed`.
+///
+/// Every frame is decoded into a `BytesMut` holding the payload of exactly one netstring. The
+/// framing is validated the same way as by [AsyncNetstringRead](crate::AsyncNetstringRead).
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::NetstringCodec;
+/// use tokio_util::codec::Framed;
+///
+/// let mut framed = Framed::new(stream, NetstringCodec::new_with_max_length(4096));
+/// let frame: BytesMut = framed.next().await.unwrap()?;
+/// framed.send(&b"Hello, World!"[..]).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct NetstringCodec {
+    max_length: usize,
+    decoder: NetstringDecoder,
+    length: Option<usize>,
+    trace: Option<FrameTrace>,
+}
+
+impl NetstringCodec {
+    /// Creates a new `NetstringCodec` without an upper limit on the frame length.
+    ///
+    /// While this may be useful during development, it should be avoided in production, since
+    /// the peer can announce arbitrary long frames, which will be buffered in memory.
+    pub fn new() -> Self {
+        Self::new_with_max_length(usize::MAX)
+    }
+
+    /// Creates a new `NetstringCodec` that rejects frames with a payload longer than
+    /// `max_length` bytes.
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        Self::new_with_options(NetstringReadOptions {
+            max_len: max_length,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a new `NetstringCodec` that decodes frames with the limits and the strictness set
+    /// in `options`. [NetstringReadOptions::max_len] applies to encoded frames as well, which
+    /// fail with `ErrorKind::InvalidInput`, while [NetstringReadOptions::oversize] is ignored, as
+    /// frames are never truncated.
+    pub fn new_with_options(options: NetstringReadOptions) -> Self {
+        NetstringCodec {
+            max_length: options.max_len,
+            decoder: NetstringDecoder::with_options(options),
+            length: None,
+            trace: None,
+        }
+    }
+
+    /// Returns the maximum payload length of a frame this codec will decode or encode.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+// The trace of the frame being decoded is left out, as it doesn't affect the decoding.
+impl PartialEq for NetstringCodec {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_length == other.max_length
+            && self.decoder == other.decoder
+            && self.length == other.length
+    }
+}
+
+impl Eq for NetstringCodec {}
+
+impl Default for NetstringCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetstringCodec {
+    fn decode_frame(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>> {
+        let length = match self.length {
+            Some(length) => length,
+            None => {
+                let (consumed, length) = match self.decoder.decode(src) {
+                    (consumed, None) => (consumed, None),
+                    (consumed, Some(DecodeEvent::Length(length))) => (consumed, Some(length)),
+                    (_, Some(DecodeEvent::Error(err))) => return Err(err.into()),
+                    (_, Some(_)) => unreachable!("the decoder emits the length first"),
+                };
+                src.advance(consumed);
+
+                match length {
+                    None => return Ok(None),
+                    Some(length) => length,
+                }
+            }
+        };
+
+        if src.len() <= length {
+            //reserve in bounded steps, so a huge announced length isn't allocated up front
+            self.length = Some(length);
+            let missing = (length - src.len()).saturating_add(1);
+            src.reserve(missing.min(COPY_BUFFER_SIZE));
+            return Ok(None);
+        }
+
+        self.decoder.decode(&src[..length]);
+        match self.decoder.decode(&src[length..=length]).1 {
+            Some(DecodeEvent::Complete) => {}
+            Some(DecodeEvent::Error(err)) => return Err(err.into()),
+            _ => unreachable!("the terminator follows the payload"),
+        }
+
+        let message = src.split_to(length);
+        src.advance(1);
+        self.length = None;
+        logging::log_netstring(logging::READING, &message);
+
+        Ok(Some(message))
+    }
+}
+
+impl Decoder for NetstringCodec {
+    type Item = BytesMut;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>> {
+        //the trace starts with the first byte, so every frame is traced separately
+        if src.is_empty() && self.trace.is_none() {
+            return Ok(None);
+        }
+
+        let trace = self
+            .trace
+            .take()
+            .unwrap_or_else(|| FrameTrace::start(Direction::Read));
+        let res = {
+            let _entered = trace.enter();
+            self.decode_frame(src)
+        };
+
+        match &res {
+            Ok(Some(message)) => trace.report(Ok(message.len()), self.decoder.frame_bytes()),
+            Ok(None) => self.trace = Some(trace),
+            Err(err) => trace.report(Err(err), self.decoder.frame_bytes()),
+        }
+        res
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>> {
+        match self.decode(src)? {
+            Some(message) => Ok(Some(message)),
+            None if src.is_empty() && self.decoder.is_at_boundary() => Ok(None),
+            None => {
+                let err = match self.length {
+                    Some(length) => NetstringError::EofInBody {
+                        missing: (length - src.len()).saturating_add(1),
+                    }
+                    .into(),
+                    None => frame::eof(&self.decoder),
+                };
+                //a buffered payload has not been passed to the decoder yet
+                if let Some(trace) = self.trace.take() {
+                    trace.report(Err(&err), self.decoder.frame_bytes() + src.len());
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+impl Encoder<&[u8]> for NetstringCodec {
+    type Error = Error;
+
+    fn encode(&mut self, data: &[u8], dst: &mut BytesMut) -> Result<()> {
+        instrument::trace_frame(
+            Direction::Write,
+            |_| data.len(),
+            || {
+                //the frame is rejected locally, so it is invalid input rather than invalid data
+                if data.len() > self.max_length {
+                    let err = NetstringError::TooLarge {
+                        len: data.len(),
+                        max: self.max_length,
+                    };
+                    return Err(Error::new(ErrorKind::InvalidInput, err));
+                }
+
+                logging::log_netstring(logging::WRITING, data);
+                let mut header = [0; MAX_HEADER_LENGTH];
+                dst.reserve(encoder::encoded_len(data.len()));
+                dst.put_slice(encoder::encode_header(data.len(), &mut header));
+                dst.put_slice(data);
+                dst.put_u8(b',');
+                Ok(())
+            },
+        )
+    }
+}
+
+impl Encoder<Bytes> for NetstringCodec {
+    type Error = Error;
+
+    fn encode(&mut self, data: Bytes, dst: &mut BytesMut) -> Result<()> {
+        self.encode(&data[..], dst)
+    }
+}
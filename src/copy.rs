@@ -0,0 +1,319 @@
+use std::future::Future;
+use std::io::ErrorKind;
+use std::marker::{PhantomPinned, Unpin};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, AsyncWrite, Result};
+
+use crate::decoder::NetstringDecoder;
+use crate::encoder::{self, MAX_HEADER_LENGTH};
+use crate::frame::{self, ReadEvent, COPY_BUFFER_SIZE};
+use crate::instrument::{Direction, FrameTrace};
+use crate::logging;
+use crate::poll::poll_read_event;
+
+/// Copies exactly one netstring from `reader` to `writer` and flushes the writer afterwards.
+///
+/// The framing is validated while the netstring is copied, so only the header, one chunk of the
+/// payload or the terminator are held in memory at a time. On success the length of the payload
+/// is returned.
+///
+/// # Usage
+/// ```no_exec
+/// let len = tokio_netstring_trait::copy_netstring(&mut client, &mut backend).await?;
+/// ```
+///
+/// # Errors
+/// The future returns the same errors as
+/// [AsyncNetstringRead::read_netstring_alloc](crate::AsyncNetstringRead::read_netstring_alloc).
+/// As everything read before the error has been forwarded already, the writer holds an
+/// incomplete netstring afterwards and both connections should be closed.
+pub fn copy_netstring<'a, R, W>(reader: &'a mut R, writer: &'a mut W) -> CopyMessage<'a, R, W>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    CopyMessage {
+        reader,
+        writer,
+        state: State::new(),
+        _pin: PhantomPinned,
+    }
+}
+
+/// Forwards every netstring from `reader` to `writer`, flushing the writer after each one.
+///
+/// The returned [RelayNetstrings] yields the length of every forwarded payload. It stops when the
+/// reader reaches EOF between two netstrings or, after yielding the error once, when the framing
+/// is invalid.
+///
+/// # Usage
+/// ```no_exec
+/// let mut relay = tokio_netstring_trait::relay_netstrings(client, backend);
+/// while let Some(len) = relay.next_frame().await? {
+///     log::debug!("forwarded {} bytes", len);
+/// }
+/// ```
+pub fn relay_netstrings<R, W>(reader: R, writer: W) -> RelayNetstrings<R, W>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    RelayNetstrings {
+        reader,
+        writer,
+        state: State::new(),
+        done: false,
+    }
+}
+
+/// The progress of copying one netstring, holding the part that has been read but not yet
+/// written in `buf[pos..cap]`.
+#[derive(Debug)]
+struct State {
+    decoder: NetstringDecoder,
+    buf: Box<[u8]>,
+    pos: usize,
+    cap: usize,
+    len: usize,
+    complete: bool,
+    trace: Option<FrameTrace>,
+}
+
+impl State {
+    fn new() -> Self {
+        State {
+            decoder: NetstringDecoder::new(),
+            buf: vec![0; COPY_BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            cap: 0,
+            len: 0,
+            complete: false,
+            trace: None,
+        }
+    }
+}
+
+fn poll_copy_netstring<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    state: &mut State,
+    cx: &mut Context<'_>,
+) -> Poll<Result<usize>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    //the trace starts with the first poll, so every netstring is traced separately
+    let trace = state
+        .trace
+        .take()
+        .unwrap_or_else(|| FrameTrace::start(Direction::Copy));
+    let res = {
+        let _entered = trace.enter();
+        poll_copy_frame(reader, writer, state, cx)
+    };
+
+    match res {
+        Poll::Ready(res) => {
+            trace.finish_read(&res, |len| *len, &state.decoder);
+            Poll::Ready(res)
+        }
+        Poll::Pending => {
+            state.trace = Some(trace);
+            Poll::Pending
+        }
+    }
+}
+
+fn poll_copy_frame<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    state: &mut State,
+    cx: &mut Context<'_>,
+) -> Poll<Result<usize>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    loop {
+        //write out what has been read, before reading more
+        if state.pos < state.cap {
+            let buf = &state.buf[state.pos..state.cap];
+            match ready_and_ok!(Pin::new(&mut *writer).poll_write(cx, buf)) {
+                0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+                wrote => state.pos += wrote,
+            }
+            //forget the chunk once it is written, so a pending read can't forward it again
+            if state.pos == state.cap {
+                state.pos = 0;
+                state.cap = 0;
+            }
+            continue;
+        }
+
+        if state.complete {
+            ready_and_ok!(Pin::new(&mut *writer).poll_flush(cx));
+            state.complete = false;
+            return Poll::Ready(Ok(state.len));
+        }
+
+        match ready_and_ok!(poll_read_event(
+            reader,
+            &mut state.decoder,
+            &mut state.buf,
+            cx
+        )) {
+            ReadEvent::Length(len) => {
+                logging::log_netstring_len(logging::COPYING, len);
+                let mut header = [0; MAX_HEADER_LENGTH];
+                let header = encoder::encode_header(len, &mut header);
+                state.buf[..header.len()].copy_from_slice(header);
+                state.cap = header.len();
+                state.len = len;
+            }
+            ReadEvent::Body(read) => state.cap = read,
+            ReadEvent::Complete => {
+                state.buf[0] = b',';
+                state.cap = 1;
+                state.complete = true;
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Creates a future which will copy exactly one message in the netstring format from a
+    /// reader to a writer and flush the writer afterwards, returning an error if EOF is hit
+    /// sooner or the framing is invalid.
+    ///
+    /// On success the length of the payload is returned
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct CopyMessage<'a, R: ?Sized, W: ?Sized> {
+        reader: &'a mut R,
+        writer: &'a mut W,
+        state: State,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+impl<R, W> Future for CopyMessage<'_, R, W>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+        let me = self.project();
+        poll_copy_netstring(&mut **me.reader, &mut **me.writer, me.state, cx)
+    }
+}
+
+/// Forwards the netstrings read from an `AsyncRead` to an `AsyncWrite`, created by
+/// [relay_netstrings].
+///
+/// The relay ends when the reader reaches EOF between two netstrings. Should the reader close
+/// in the middle of a netstring or the framing be invalid, the error is returned once and the
+/// relay ends afterwards, as the position in the underlying stream is lost.
+#[derive(Debug)]
+pub struct RelayNetstrings<R, W> {
+    reader: R,
+    writer: W,
+    state: State,
+    done: bool,
+}
+
+impl<R, W> RelayNetstrings<R, W>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    /// Forwards the next netstring and returns the length of its payload, or `None` if the
+    /// relay has ended.
+    pub fn next_frame(&mut self) -> RelayFrame<'_, R, W> {
+        RelayFrame { relay: self }
+    }
+
+    /// Polls to forward the next netstring, returning the length of its payload, or `None` if
+    /// the relay has ended.
+    pub fn poll_next_frame(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<usize>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        match ready!(poll_copy_netstring(
+            &mut self.reader,
+            &mut self.writer,
+            &mut self.state,
+            cx
+        )) {
+            Ok(len) => Poll::Ready(Some(Ok(len))),
+            Err(err) => {
+                self.done = true;
+                match frame::is_eof_at_boundary(&err, &self.state.decoder) {
+                    true => Poll::Ready(None),
+                    false => Poll::Ready(Some(Err(err))),
+                }
+            }
+        }
+    }
+}
+
+impl<R, W> RelayNetstrings<R, W> {
+    /// Returns shared references to the underlying reader and writer.
+    pub fn get_ref(&self) -> (&R, &W) {
+        (&self.reader, &self.writer)
+    }
+
+    /// Returns mutable references to the underlying reader and writer.
+    pub fn get_mut(&mut self) -> (&mut R, &mut W) {
+        (&mut self.reader, &mut self.writer)
+    }
+
+    /// Consumes the relay, returning the underlying reader and writer. Should a netstring be
+    /// partially forwarded, the writer holds an incomplete netstring.
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<R, W> futures_core::Stream for RelayNetstrings<R, W>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    type Item = Result<usize>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<usize>>> {
+        self.get_mut().poll_next_frame(cx)
+    }
+}
+
+/// Creates a future which will forward the next netstring of a [RelayNetstrings], created by
+/// [RelayNetstrings::next_frame].
+///
+/// On success the length of the payload is returned, or `None` if the relay has ended
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RelayFrame<'a, R, W> {
+    relay: &'a mut RelayNetstrings<R, W>,
+}
+
+impl<R, W> Future for RelayFrame<'_, R, W>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    type Output = Result<Option<usize>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Option<usize>>> {
+        let me = self.get_mut();
+        Poll::Ready(ready!(me.relay.poll_next_frame(cx)).transpose())
+    }
+}
@@ -0,0 +1,349 @@
+use std::str::FromStr;
+
+use serde::de::{self, DeserializeSeed, Visitor};
+use serde::Deserialize;
+
+use crate::error::SerdeError;
+use crate::list::decode_list;
+
+/// Deserializes a value from one netstring written by [to_netstring](crate::to_netstring).
+///
+/// Strings and bytes are borrowed from `input`, if the value allows it. As the format does not
+/// describe the type of a netstring, `deserialize_any` yields every netstring as a string, or as
+/// bytes if it isn't valid UTF-8, so untagged enums and flattened structs see text only.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::from_netstring;
+///
+/// let value: (&str, u32, Vec<bool>) = from_netstring(b"23:5:Hello,2:13,7:4:true,,,")?;
+/// assert_eq!(("Hello", 13, vec![true]), value);
+/// ```
+///
+/// # Errors
+/// It returns [SerdeError::Netstring] if the framing of a netstring is invalid,
+/// [SerdeError::InvalidScalar] if a netstring can't be parsed as the scalar expected and
+/// [SerdeError::MissingItem] or [SerdeError::TrailingData] if a list doesn't hold as many items
+/// as the value.
+pub fn from_netstring<'de, T>(input: &'de [u8]) -> Result<T, SerdeError>
+where
+    T: Deserialize<'de>,
+{
+    Deserializer {
+        input,
+        pending: None,
+    }
+    .value()
+}
+
+/// Deserializes a value from the payload of a netstring, that has been read already.
+pub(crate) fn from_payload<'de, T>(payload: &'de [u8]) -> Result<T, SerdeError>
+where
+    T: Deserialize<'de>,
+{
+    Deserializer {
+        input: &[],
+        pending: Some(payload),
+    }
+    .value()
+}
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+    pending: Option<&'de [u8]>,
+}
+
+impl<'de> Deserializer<'de> {
+    fn value<T: Deserialize<'de>>(mut self) -> Result<T, SerdeError> {
+        let value = T::deserialize(&mut self)?;
+        self.end()?;
+        Ok(value)
+    }
+
+    fn end(&self) -> Result<(), SerdeError> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(SerdeError::TrailingData),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input.is_empty() && self.pending.is_none()
+    }
+
+    /// Returns the payload of the next netstring.
+    fn next(&mut self) -> Result<&'de [u8], SerdeError> {
+        if let Some(payload) = self.pending.take() {
+            return Ok(payload);
+        }
+
+        let mut list = decode_list(self.input);
+        match list.next() {
+            None => Err(SerdeError::MissingItem),
+            Some(Err(err)) => Err(err.into()),
+            Some(Ok(payload)) => {
+                self.input = list.rest();
+                Ok(payload)
+            }
+        }
+    }
+
+    /// Returns a deserializer over the netstrings nested in the next netstring.
+    fn nested(&mut self) -> Result<Deserializer<'de>, SerdeError> {
+        Ok(Deserializer {
+            input: self.next()?,
+            pending: None,
+        })
+    }
+
+    fn text(&mut self) -> Result<&'de str, SerdeError> {
+        std::str::from_utf8(self.next()?).map_err(|_| SerdeError::InvalidScalar {
+            expected: "UTF-8 text",
+        })
+    }
+
+    fn parse<T: FromStr>(&mut self, expected: &'static str) -> Result<T, SerdeError> {
+        self.text()?
+            .parse()
+            .map_err(|_| SerdeError::InvalidScalar { expected })
+    }
+}
+
+macro_rules! deserialize_parse {
+    ($($method:ident => $visit:ident, $expected:literal;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+                visitor.$visit(self.parse($expected)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = SerdeError;
+
+    deserialize_parse! {
+        deserialize_i8 => visit_i8, "a decimal i8";
+        deserialize_i16 => visit_i16, "a decimal i16";
+        deserialize_i32 => visit_i32, "a decimal i32";
+        deserialize_i64 => visit_i64, "a decimal i64";
+        deserialize_i128 => visit_i128, "a decimal i128";
+        deserialize_u8 => visit_u8, "a decimal u8";
+        deserialize_u16 => visit_u16, "a decimal u16";
+        deserialize_u32 => visit_u32, "a decimal u32";
+        deserialize_u64 => visit_u64, "a decimal u64";
+        deserialize_u128 => visit_u128, "a decimal u128";
+        deserialize_f32 => visit_f32, "a decimal f32";
+        deserialize_f64 => visit_f64, "a decimal f64";
+        deserialize_char => visit_char, "a single char";
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let payload = self.next()?;
+        match std::str::from_utf8(payload) {
+            Ok(text) => visitor.visit_borrowed_str(text),
+            Err(_) => visitor.visit_borrowed_bytes(payload),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.text()? {
+            "true" => visitor.visit_bool(true),
+            "false" => visitor.visit_bool(false),
+            _ => Err(SerdeError::InvalidScalar {
+                expected: "true or false",
+            }),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_str(self.text()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_bytes(self.next()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let mut nested = self.nested()?;
+        if nested.is_empty() {
+            return visitor.visit_none();
+        }
+        let value = visitor.visit_some(&mut nested)?;
+        nested.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.next()?.is_empty() {
+            true => visitor.visit_unit(),
+            false => Err(SerdeError::InvalidScalar {
+                expected: "an empty netstring",
+            }),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let mut nested = self.nested()?;
+        let value = visitor.visit_seq(&mut nested)?;
+        nested.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let mut nested = self.nested()?;
+        let value = visitor.visit_map(&mut nested)?;
+        nested.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let mut nested = self.nested()?;
+        let value = visitor.visit_enum(&mut nested)?;
+        nested.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.next()?;
+        visitor.visit_unit()
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Deserializer<'de> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, SerdeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.is_empty() {
+            true => Ok(None),
+            false => seed.deserialize(self).map(Some),
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Deserializer<'de> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, SerdeError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.is_empty() {
+            true => Ok(None),
+            false => seed.deserialize(self).map(Some),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, SerdeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = SerdeError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), SerdeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(&mut *self)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, SerdeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,265 @@
+use crate::encoder::MAX_HEADER_LENGTH;
+use crate::error::NetstringError;
+use crate::options::{NetstringReadOptions, Strictness};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Length { length: usize, digits: usize },
+    Body { remaining: usize },
+    Terminator,
+    Failed(NetstringError),
+}
+
+/// An event emitted by the [NetstringDecoder].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeEvent<'a> {
+    /// The length header has been parsed. The netstring carries this many bytes of payload.
+    Length(usize),
+    /// A chunk of the payload. It borrows from the input given to
+    /// [NetstringDecoder::decode].
+    Body(&'a [u8]),
+    /// The terminator has been verified and the netstring is complete. The decoder is ready to
+    /// decode the next netstring.
+    Complete,
+    /// The input is not a valid netstring. The decoder stays in this state and keeps returning
+    /// the error, as the position in the stream is lost.
+    Error(NetstringError),
+}
+
+/// A push-style netstring decoder, that does not perform any io itself.
+///
+/// The input can be fed in chunks of arbitrary size. Every call to [NetstringDecoder::decode]
+/// consumes input until the next event occurs, so the caller can drive any kind of reader with
+/// it, be it async, blocking or completion based.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::{DecodeEvent, NetstringDecoder};
+///
+/// let mut decoder = NetstringDecoder::new();
+/// let mut input: &[u8] = b"5:Hello,";
+///
+/// while !input.is_empty() {
+///     let (consumed, event) = decoder.decode(input);
+///     input = &input[consumed..];
+///
+///     match event {
+///         Some(DecodeEvent::Body(chunk)) => message.extend_from_slice(chunk),
+///         Some(DecodeEvent::Error(err)) => return Err(err),
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NetstringDecoder {
+    state: State,
+    options: NetstringReadOptions,
+    // The bytes consumed for the current netstring, or for the last one until the next byte
+    // has been consumed.
+    bytes: usize,
+    complete: bool,
+}
+
+impl NetstringDecoder {
+    /// Creates a new decoder, expecting the length header of a netstring.
+    pub fn new() -> Self {
+        Self::with_options(NetstringReadOptions::default())
+    }
+
+    /// Creates a new decoder, that enforces the limits set in `options`.
+    pub fn with_options(options: NetstringReadOptions) -> Self {
+        NetstringDecoder {
+            state: State::Length {
+                length: 0,
+                digits: 0,
+            },
+            options,
+            bytes: 0,
+            complete: false,
+        }
+    }
+
+    /// Creates a decoder, that continues with the payload of a netstring of length `len`,
+    /// whose header has already been consumed.
+    #[cfg(feature = "std")]
+    pub(crate) fn resume(len: usize, options: NetstringReadOptions) -> Self {
+        let state = match len {
+            0 => State::Terminator,
+            remaining => State::Body { remaining },
+        };
+        NetstringDecoder {
+            state,
+            options,
+            bytes: 0,
+            complete: false,
+        }
+    }
+
+    /// Consumes `input` until the next event occurs. It returns the number of bytes consumed and
+    /// the event. Should `input` be consumed completely without completing an event, `None` is
+    /// returned and the decoder waits for more input.
+    ///
+    /// A [DecodeEvent::Body] chunk never extends beyond the payload, so the bytes following the
+    /// returned count belong to the terminator or the next netstring.
+    pub fn decode<'a>(&mut self, input: &'a [u8]) -> (usize, Option<DecodeEvent<'a>>) {
+        let (consumed, event) = self.decode_event(input);
+        if consumed > 0 {
+            self.bytes = match self.complete {
+                true => consumed,
+                false => self.bytes.saturating_add(consumed),
+            };
+            self.complete = event == Some(DecodeEvent::Complete);
+        }
+        (consumed, event)
+    }
+
+    fn decode_event<'a>(&mut self, input: &'a [u8]) -> (usize, Option<DecodeEvent<'a>>) {
+        let mut consumed = 0;
+
+        while consumed < input.len() {
+            match &mut self.state {
+                State::Length { length, digits } => {
+                    let byte = input[consumed];
+                    consumed += 1;
+
+                    match byte {
+                        b'0'..=b'9'
+                            if *digits > 0
+                                && *length == 0
+                                && self.options.strictness == Strictness::Strict =>
+                        {
+                            return self.fail(consumed, NetstringError::LeadingZero)
+                        }
+                        //bound the header, as leading zeros would never overflow the length
+                        b'0'..=b'9' if *digits >= MAX_HEADER_LENGTH - 1 => {
+                            return self.fail(consumed, NetstringError::LengthOverflow)
+                        }
+                        b'0'..=b'9' => {
+                            match length
+                                .checked_mul(10)
+                                .and_then(|len| len.checked_add((byte - b'0') as usize))
+                            {
+                                Some(len) => *length = len,
+                                None => return self.fail(consumed, NetstringError::LengthOverflow),
+                            }
+                            *digits += 1;
+                        }
+                        b':' if *digits > 0 => {
+                            let length = *length;
+                            if length > self.options.max_len {
+                                let max = self.options.max_len;
+                                return self
+                                    .fail(consumed, NetstringError::TooLarge { len: length, max });
+                            }
+                            self.state = match length {
+                                0 => State::Terminator,
+                                remaining => State::Body { remaining },
+                            };
+                            return (consumed, Some(DecodeEvent::Length(length)));
+                        }
+                        byte if *digits == 0 => {
+                            return self.fail(consumed, NetstringError::InvalidLengthDigit(byte))
+                        }
+                        byte => return self.fail(consumed, NetstringError::BadSeparator(byte)),
+                    }
+                }
+
+                State::Body { remaining } => {
+                    let chunk = &input[consumed..];
+                    let chunk = &chunk[..chunk.len().min(*remaining)];
+                    *remaining -= chunk.len();
+                    if *remaining == 0 {
+                        self.state = State::Terminator;
+                    }
+                    return (consumed + chunk.len(), Some(DecodeEvent::Body(chunk)));
+                }
+
+                State::Terminator => {
+                    return match input[consumed] {
+                        b',' => {
+                            self.state = State::Length {
+                                length: 0,
+                                digits: 0,
+                            };
+                            (consumed + 1, Some(DecodeEvent::Complete))
+                        }
+                        byte => self.fail(consumed + 1, NetstringError::BadTerminator(byte)),
+                    };
+                }
+
+                State::Failed(err) => return (consumed, Some(DecodeEvent::Error(*err))),
+            }
+        }
+
+        match self.state {
+            State::Failed(err) => (consumed, Some(DecodeEvent::Error(err))),
+            _ => (consumed, None),
+        }
+    }
+
+    /// Returns the number of payload bytes that are still expected for the current netstring.
+    /// It is `0` while the header or the terminator is being decoded.
+    pub fn body_remaining(&self) -> usize {
+        match self.state {
+            State::Body { remaining } => remaining,
+            _ => 0,
+        }
+    }
+
+    /// Returns the error describing an end of the input in the current state, e.g. to be
+    /// returned once a reader reports EOF.
+    pub fn eof_error(&self) -> NetstringError {
+        match self.state {
+            State::Length { .. } => NetstringError::EofInHeader,
+            State::Body { remaining } => NetstringError::EofInBody {
+                missing: remaining.saturating_add(1),
+            },
+            State::Terminator => NetstringError::EofInBody { missing: 1 },
+            State::Failed(err) => err,
+        }
+    }
+
+    /// Returns true if the decoder is between two netstrings, i.e. no byte of the next netstring
+    /// has been consumed yet.
+    pub fn is_at_boundary(&self) -> bool {
+        matches!(
+            self.state,
+            State::Length {
+                length: 0,
+                digits: 0
+            }
+        )
+    }
+
+    /// Returns the number of bytes consumed for the current netstring, including the byte an
+    /// error has been detected at. Once a netstring is complete, it returns the bytes of that
+    /// netstring, until the first byte of the next one is consumed.
+    #[cfg(feature = "std")]
+    pub(crate) fn frame_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    fn fail<'a>(
+        &mut self,
+        consumed: usize,
+        err: NetstringError,
+    ) -> (usize, Option<DecodeEvent<'a>>) {
+        self.state = State::Failed(err);
+        (consumed, Some(DecodeEvent::Error(err)))
+    }
+}
+
+// The byte count is bookkeeping for the metrics, so it does not tell two decoders apart.
+impl PartialEq for NetstringDecoder {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state && self.options == other.options
+    }
+}
+
+impl Eq for NetstringDecoder {}
+
+impl Default for NetstringDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The maximum length of a netstring header, `usize::MAX` in decimal followed by a `b':'`.
+pub const MAX_HEADER_LENGTH: usize = 21;
+
+/// Returns the number of bytes `data_len` bytes of payload take up, once framed as a netstring.
+pub fn encoded_len(data_len: usize) -> usize {
+    let mut digits = 1;
+    let mut rest = data_len / 10;
+    while rest > 0 {
+        digits += 1;
+        rest /= 10;
+    }
+    digits + data_len + 2
+}
+
+/// Writes the `len:` header of a netstring with `data_len` bytes of payload into `buf` and
+/// returns the part of `buf` holding it.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::{encode_header, MAX_HEADER_LENGTH};
+///
+/// let mut buf = [0; MAX_HEADER_LENGTH];
+/// assert_eq!(b"13:", encode_header(13, &mut buf));
+/// ```
+pub fn encode_header(data_len: usize, buf: &mut [u8; MAX_HEADER_LENGTH]) -> &[u8] {
+    let mut digits = data_len;
+    let mut start = MAX_HEADER_LENGTH - 1;
+    buf[start] = b':';
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (digits % 10) as u8;
+        digits /= 10;
+        if digits == 0 {
+            break;
+        }
+    }
+    &buf[start..]
+}
+
+/// Writes `data` framed as a netstring into `buf` and returns the number of bytes written.
+/// Should `buf` be shorter than [encoded_len] of the payload, `None` is returned and `buf` is
+/// left untouched.
+pub fn encode_to_slice(data: &[u8], buf: &mut [u8]) -> Option<usize> {
+    let len = encoded_len(data.len());
+    if buf.len() < len {
+        return None;
+    }
+
+    let mut header = [0; MAX_HEADER_LENGTH];
+    let header = encode_header(data.len(), &mut header);
+    let (head, rest) = buf.split_at_mut(header.len());
+    head.copy_from_slice(header);
+    rest[..data.len()].copy_from_slice(data);
+    rest[data.len()] = b',';
+
+    Some(len)
+}
+
+/// Appends `data` framed as a netstring to `buf`.
+#[cfg(feature = "alloc")]
+pub fn encode_to_vec(data: &[u8], buf: &mut Vec<u8>) {
+    let mut header = [0; MAX_HEADER_LENGTH];
+    buf.reserve(encoded_len(data.len()));
+    buf.extend_from_slice(encode_header(data.len(), &mut header));
+    buf.extend_from_slice(data);
+    buf.push(b',');
+}
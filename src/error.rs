@@ -0,0 +1,191 @@
+use core::fmt;
+
+/// The reasons why a netstring can't be read. They are either returned by the
+/// [NetstringDecoder](crate::NetstringDecoder) directly or wrapped in a `std::io::Error` by the
+/// readers, from which they can be recovered with
+/// `err.get_ref().and_then(|err| err.downcast_ref::<NetstringError>())`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NetstringError {
+    /// The length header starts with a byte that is not an ascii digit.
+    InvalidLengthDigit(u8),
+    /// The length header does not fit into a `usize` or, counting leading zeros, is longer than
+    /// [MAX_HEADER_LENGTH](crate::MAX_HEADER_LENGTH) without the separator.
+    LengthOverflow,
+    /// The length header starts with a zero followed by more digits, which
+    /// [Strictness::Strict](crate::Strictness::Strict) rejects.
+    LeadingZero,
+    /// The length header is not followed by a `b':'`.
+    BadSeparator(u8),
+    /// The payload is not followed by a `b','`.
+    BadTerminator(u8),
+    /// The length header announces more payload than
+    /// [NetstringReadOptions::max_len](crate::NetstringReadOptions::max_len) allows.
+    TooLarge {
+        /// The length announced by the header.
+        len: usize,
+        /// The maximum length allowed.
+        max: usize,
+    },
+    /// The stream ended before the length header was complete.
+    EofInHeader,
+    /// The stream ended after the length header, but before the netstring was complete.
+    EofInBody {
+        /// The number of bytes missing, including the terminator.
+        missing: usize,
+    },
+}
+
+impl fmt::Display for NetstringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetstringError::InvalidLengthDigit(byte) => write!(
+                f,
+                "ERROR: Expected a length digit but found {} instead",
+                *byte as char
+            ),
+            NetstringError::LengthOverflow => {
+                write!(f, "ERROR: Integer overflow while parsing message length.")
+            }
+            NetstringError::LeadingZero => {
+                write!(f, "ERROR: Leading zero in the message length.")
+            }
+            NetstringError::BadSeparator(byte) => write!(
+                f,
+                "ERROR: Expected separator ':' but found {} instead",
+                *byte as char
+            ),
+            NetstringError::BadTerminator(byte) => write!(
+                f,
+                "ERROR: Expected terminator ',' but found {} instead",
+                *byte as char
+            ),
+            NetstringError::TooLarge { len, max } => write!(
+                f,
+                "ERROR: Message of length {} exceeds the maximum length of {}",
+                len, max
+            ),
+            NetstringError::EofInHeader => write!(f, "ERROR: Early eof in the length header"),
+            NetstringError::EofInBody { missing } => write!(
+                f,
+                "ERROR: Early eof with {} bytes of the message missing",
+                missing
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NetstringError {}
+
+/// Converts into an error of kind `ErrorKind::UnexpectedEof` for the EOF variants and
+/// `ErrorKind::InvalidData` for all others.
+#[cfg(feature = "std")]
+impl From<NetstringError> for std::io::Error {
+    fn from(err: NetstringError) -> Self {
+        let kind = match err {
+            NetstringError::EofInHeader | NetstringError::EofInBody { .. } => {
+                std::io::ErrorKind::UnexpectedEof
+            }
+            _ => std::io::ErrorKind::InvalidData,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
+/// The error returned when the buffer given to
+/// [AsyncNetstringRead::read_netstring](crate::AsyncNetstringRead::read_netstring) is too small
+/// for the netstring announced by the length header.
+///
+/// It is wrapped in a `std::io::Error` of kind `ErrorKind::InvalidInput` and can be retrieved
+/// with `err.get_ref().and_then(|err| err.downcast_ref::<BufferTooSmall>())`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    /// The length announced by the header, i.e. the size of the buffer needed.
+    pub len: usize,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ERROR: Output buffer to small for message of length {}",
+            self.len
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BufferTooSmall {}
+
+/// The reasons why a value can't be converted to or from the nested netstring format of
+/// [to_netstring](crate::to_netstring) and [from_netstring](crate::from_netstring).
+///
+/// The async and blocking readers and writers wrap it in a `std::io::Error` of kind
+/// `ErrorKind::InvalidData`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SerdeError {
+    /// An error raised by the `Serialize` or `Deserialize` implementation of the value.
+    Message(std::string::String),
+    /// The input is not a valid netstring.
+    Netstring(NetstringError),
+    /// A netstring does not hold the expected scalar, e.g. a decimal number.
+    InvalidScalar {
+        /// A description of the expected scalar.
+        expected: &'static str,
+    },
+    /// A list ended before all items of the value were read.
+    MissingItem,
+    /// The input or a list holds more netstrings than the value consumed.
+    TrailingData,
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerdeError::Message(msg) => write!(f, "ERROR: {}", msg),
+            SerdeError::Netstring(err) => err.fmt(f),
+            SerdeError::InvalidScalar { expected } => {
+                write!(f, "ERROR: Expected {} in the netstring", expected)
+            }
+            SerdeError::MissingItem => write!(f, "ERROR: The list ended before the value"),
+            SerdeError::TrailingData => write!(f, "ERROR: Netstrings left after the value"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for SerdeError {}
+
+#[cfg(feature = "serde")]
+impl From<NetstringError> for SerdeError {
+    fn from(err: NetstringError) -> Self {
+        SerdeError::Netstring(err)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SerdeError> for std::io::Error {
+    fn from(err: SerdeError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeError::Message(msg.to_string())
+    }
+}
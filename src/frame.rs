@@ -0,0 +1,183 @@
+use std::convert::TryFrom;
+use std::io::{Error, ErrorKind, IoSlice};
+use std::ops::Deref;
+
+use crate::decoder::NetstringDecoder;
+use crate::encoder::{self, MAX_HEADER_LENGTH};
+use crate::error::{BufferTooSmall, NetstringError};
+
+/// Payloads up to this length are copied together with the header and the terminator into one
+/// buffer, if the writer does not support vectored writes, so they are written with one call.
+pub(crate) const COALESCE_LIMIT: usize = 1024;
+
+/// The size of the buffer payloads are copied through, when they are streamed.
+#[cfg(feature = "tokio")]
+pub(crate) const COPY_BUFFER_SIZE: usize = 8 * 1024;
+
+/// The events of the [NetstringDecoder](crate::NetstringDecoder), after the payload has been
+/// read into a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReadEvent {
+    Length(usize),
+    Body(usize),
+    Complete,
+}
+
+pub(crate) fn eof(decoder: &NetstringDecoder) -> Error {
+    decoder.eof_error().into()
+}
+
+/// Returns true if `err` reports that the stream ended before the first byte of a netstring,
+/// which is a clean end rather than a protocol error.
+pub(crate) fn is_eof_at_boundary(err: &Error, decoder: &NetstringDecoder) -> bool {
+    err.kind() == ErrorKind::UnexpectedEof && decoder.is_at_boundary()
+}
+
+/// Converts an error in the nested netstrings of a list, which is invalid data even if the
+/// outer payload ended early.
+pub(crate) fn invalid_list(err: NetstringError) -> Error {
+    Error::new(ErrorKind::InvalidData, err)
+}
+
+/// Decodes the nested netstrings of a list into owned payloads.
+pub(crate) fn decode_list(payload: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+    crate::list::decode_list(payload)
+        .map(|item| item.map(<[u8]>::to_vec).map_err(invalid_list))
+        .collect()
+}
+
+/// Returns the length of the payload of an encoded netstring.
+pub(crate) fn payload_len(netstring: &[u8]) -> usize {
+    netstring
+        .iter()
+        .position(|byte| *byte == b':')
+        .map_or(0, |pos| netstring.len().saturating_sub(pos + 2))
+}
+
+pub(crate) fn buffer_to_small(len: usize) -> Error {
+    Error::new(ErrorKind::InvalidInput, BufferTooSmall { len })
+}
+
+pub(crate) fn buffer_to_small_fatal(len: usize) -> Error {
+    Error::new(ErrorKind::InvalidData, BufferTooSmall { len })
+}
+
+pub(crate) fn source_too_short(copied: usize, len: usize) -> Error {
+    Error::new(
+        ErrorKind::UnexpectedEof,
+        format!(
+            "ERROR: Source ended after {} of the announced {} bytes",
+            copied, len
+        ),
+    )
+}
+
+pub(crate) fn source_too_long(len: usize) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("ERROR: Source holds more than the announced {} bytes", len),
+    )
+}
+
+/// The number of bytes a payload buffer grows by at least.
+const PAYLOAD_GROWTH: usize = 8 * 1024;
+
+/// Grows `buf` for the next chunk of a payload, of which `remaining` bytes have not been read
+/// yet. The buffer is doubled up to the announced length, so the memory held follows the bytes
+/// received rather than the length announced by the peer, and a failed allocation is returned
+/// as an error of kind `ErrorKind::OutOfMemory` instead of aborting.
+pub(crate) fn grow_payload(buf: &mut Vec<u8>, remaining: usize) -> Result<(), Error> {
+    let grow = remaining.min(buf.len().max(PAYLOAD_GROWTH));
+    buf.try_reserve_exact(grow).map_err(|_| {
+        Error::new(
+            ErrorKind::OutOfMemory,
+            format!("ERROR: Can't allocate {} more bytes for the payload", grow),
+        )
+    })?;
+    buf.resize(buf.len() + grow, 0);
+    Ok(())
+}
+
+/// Converts the length of a file into the length of a netstring.
+pub(crate) fn file_len(len: u64) -> Result<usize, Error> {
+    usize::try_from(len).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("ERROR: File of length {} exceeds usize::MAX", len),
+        )
+    })
+}
+
+/// The maximum number of slices passed to one vectored write.
+const MAX_IO_SLICES: usize = 16;
+
+/// A netstring to be written, split into the header, the parts of the payload and the
+/// terminator, so the payload does not have to be copied.
+#[derive(Debug)]
+pub(crate) struct Frame<'a, T> {
+    header: [u8; MAX_HEADER_LENGTH],
+    header_start: usize,
+    parts: &'a [T],
+    len: usize,
+}
+
+impl<'a, T: Deref<Target = [u8]>> Frame<'a, T> {
+    pub(crate) fn new(parts: &'a [T]) -> Self {
+        let len = parts.iter().map(|part| part.len()).sum();
+        let mut header = [0; MAX_HEADER_LENGTH];
+        let header_start = MAX_HEADER_LENGTH - encoder::encode_header(len, &mut header).len();
+        Frame {
+            header,
+            header_start,
+            parts,
+            len,
+        }
+    }
+
+    /// The length of the whole netstring.
+    pub(crate) fn encoded_len(&self) -> usize {
+        MAX_HEADER_LENGTH - self.header_start + self.len + 1
+    }
+
+    /// The length of the payload.
+    pub(crate) fn data_len(&self) -> usize {
+        self.len
+    }
+
+    /// Copies the whole netstring into one buffer.
+    pub(crate) fn to_vec(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.encoded_len());
+        buf.extend_from_slice(&self.header[self.header_start..]);
+        for part in self.parts {
+            buf.extend_from_slice(part);
+        }
+        buf.push(b',');
+        buf
+    }
+
+    /// Returns the slices of the netstring, that have not been written after `written` bytes,
+    /// and their number. Only the first ones are returned, should there be too many parts.
+    pub(crate) fn remaining(&self, mut written: usize) -> ([IoSlice<'_>; MAX_IO_SLICES], usize) {
+        let header = &self.header[self.header_start..];
+        let parts = self.parts.iter().map(|part| &part[..]);
+        let all = std::iter::once(header)
+            .chain(parts)
+            .chain(std::iter::once(&b","[..]));
+
+        let mut slices = [IoSlice::new(&[]); MAX_IO_SLICES];
+        let mut len = 0;
+        for part in all {
+            if len == MAX_IO_SLICES {
+                break;
+            } else if written >= part.len() {
+                written -= part.len();
+            } else {
+                slices[len] = IoSlice::new(&part[written..]);
+                written = 0;
+                len += 1;
+            }
+        }
+
+        (slices, len)
+    }
+}
@@ -0,0 +1,167 @@
+use std::io::{Error, Result};
+
+use crate::decoder::NetstringDecoder;
+use crate::encoder::encoded_len;
+use crate::frame;
+use crate::metrics;
+
+/// The kind of operation performed on a netstring, recorded as the `direction` field and
+/// reported to the installed [NetstringMetrics](crate::NetstringMetrics). A copied netstring is
+/// reported as read and written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Read,
+    Write,
+    Drop,
+    #[cfg(feature = "tokio")]
+    Copy,
+}
+
+impl Direction {
+    #[cfg(feature = "tracing")]
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Read => "read",
+            Direction::Write => "write",
+            Direction::Drop => "drop",
+            #[cfg(feature = "tokio")]
+            Direction::Copy => "copy",
+        }
+    }
+}
+
+/// Traces the read, write or drop of one netstring. Once it is done, the outcome is reported to
+/// the installed metrics. With the `tracing` feature it also holds a span, that is entered while
+/// the frame is processed, and emits an event once it is done.
+#[derive(Debug, Clone)]
+pub(crate) struct FrameTrace {
+    direction: Direction,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) type Entered<'a> = tracing::span::Entered<'a>;
+#[cfg(not(feature = "tracing"))]
+pub(crate) type Entered<'a> = std::marker::PhantomData<&'a ()>;
+
+impl FrameTrace {
+    /// Starts to trace a frame. The span is a child of the span current to the caller.
+    pub(crate) fn start(direction: Direction) -> Self {
+        FrameTrace {
+            direction,
+            #[cfg(feature = "tracing")]
+            span: tracing::trace_span!(
+                target: "tokio_netstring_trait",
+                "netstring_frame",
+                direction = direction.as_str()
+            ),
+            #[cfg(feature = "tracing")]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Enters the span of the frame until the guard is dropped.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn enter(&self) -> Entered<'_> {
+        self.span.enter()
+    }
+
+    /// Enters the span of the frame until the guard is dropped.
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn enter(&self) -> Entered<'_> {
+        std::marker::PhantomData
+    }
+
+    /// Works like [FrameTrace::finish] for a read or a drop, but counts the bytes `decoder`
+    /// consumed and reports nothing, should the stream have ended cleanly before the first byte
+    /// of the netstring.
+    pub(crate) fn finish_read<T>(
+        &self,
+        res: &Result<T>,
+        len: impl FnOnce(&T) -> usize,
+        decoder: &NetstringDecoder,
+    ) {
+        match res {
+            Err(err) if frame::is_eof_at_boundary(err, decoder) => {}
+            res => self.report(res.as_ref().map(len), decoder.frame_bytes()),
+        }
+    }
+
+    /// Reports the outcome of a written frame and emits the event describing it, where `len`
+    /// returns the length of its payload on success.
+    pub(crate) fn finish<T>(&self, res: &Result<T>, len: impl FnOnce(&T) -> usize) {
+        let res = res.as_ref().map(len);
+        self.report(res, res.map_or(0, encoded_len));
+    }
+
+    /// Reports the outcome of the frame like [FrameTrace::finish], where `res` holds the length
+    /// of its payload on success and `bytes` counts the bytes of the frame read from or written
+    /// to the stream.
+    pub(crate) fn report(&self, res: std::result::Result<usize, &Error>, bytes: usize) {
+        metrics::record_frame(self.direction, res, bytes);
+
+        #[cfg(feature = "tracing")]
+        {
+            let _entered = self.span.enter();
+            let elapsed = self.start.elapsed();
+            let direction = self.direction.as_str();
+            match res {
+                Ok(len) => tracing::debug!(
+                    target: "tokio_netstring_trait",
+                    direction,
+                    len,
+                    ?elapsed,
+                    outcome = "ok",
+                    "netstring frame done"
+                ),
+                Err(err) => tracing::debug!(
+                    target: "tokio_netstring_trait",
+                    direction,
+                    ?elapsed,
+                    outcome = "error",
+                    error_kind = ?err.kind(),
+                    error = %err,
+                    "netstring frame failed"
+                ),
+            }
+        }
+    }
+}
+
+/// Traces the blocking write of one netstring performed by `frame`, where `len` returns the
+/// length of its payload on success.
+pub(crate) fn trace_frame<T>(
+    direction: Direction,
+    len: impl FnOnce(&T) -> usize,
+    frame: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let trace = FrameTrace::start(direction);
+    let res = {
+        let _entered = trace.enter();
+        frame()
+    };
+
+    trace.finish(&res, len);
+    res
+}
+
+/// Traces the blocking read or drop of one netstring performed by `frame` with `decoder`, like
+/// [FrameTrace::finish_read].
+pub(crate) fn trace_read_frame<T>(
+    direction: Direction,
+    len: impl FnOnce(&T) -> usize,
+    decoder: &mut NetstringDecoder,
+    frame: impl FnOnce(&mut NetstringDecoder) -> Result<T>,
+) -> Result<T> {
+    let trace = FrameTrace::start(direction);
+    let res = {
+        let _entered = trace.enter();
+        frame(decoder)
+    };
+
+    trace.finish_read(&res, len, decoder);
+    res
+}
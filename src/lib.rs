@@ -4,93 +4,160 @@
     rust_2018_idioms,
     unreachable_pub
 )]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! # NOTICE
 //! This is the very first release and my first project in rust. Feedback is appreciated.
+//!
+//! # Features
+//! The [NetstringDecoder], [decode_list] and the `encode_*` functions work without `std`, which
+//! makes them usable on embedded targets and in WASM. Everything else is opt-in:
+//!
+//! - `alloc`: Adds [encode_to_vec] and [encode_list_to_vec].
+//! - `std`: Adds the blocking [NetstringRead] and [NetstringWrite] traits, the
+//!   [PayloadLogging] setting for their trace logs and the [NetstringMetrics] hook. Implies
+//!   `alloc`.
+//! - `tokio` (default): Adds the [AsyncNetstringRead] and [AsyncNetstringWrite] traits, as well
+//!   as [copy_netstring], [relay_netstrings] and the cancel safe [NetstringReader]. Implies
+//!   `std`.
+//! - `codec`: Adds the [NetstringCodec] for `tokio_util::codec`.
+//! - `stream`: Adds the [Netstrings] stream and the [NetstringSink].
+//! - `serde`: Adds [to_netstring] and [from_netstring], a serde data format nesting
+//!   netstrings, as well as methods to read and write values with it. Implies `std`.
+//! - `fs`: Adds [AsyncNetstringWrite::write_netstring_from_file] for `tokio::fs::File`.
+//! - `tracing`: Every read, write, drop and copy of a netstring emits a `debug` event with the
+//!   fields `direction`, `len`, `elapsed` and `outcome`, plus `error_kind` and `error` on
+//!   failure. The frame is processed inside a `netstring_frame` span at `trace` level, which is
+//!   a child of the caller's span, if the subscriber enables it. The codec and the sink report
+//!   a written frame once it is encoded into their buffer, and a [NetstringBody] once its
+//!   terminator has been read. Implies `std`.
 
-use async_trait::async_trait;
-use log::trace;
-use std::io;
-use std::io::{Cursor, ErrorKind, Write};
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-// The length of a netstring is encoded in decimal. A u32 in decimal is 10 characters long.
-// The assumption is made that messages larger than u32::MAX are faulty packages and
-// they will therefore not be processed.
-const MAX_NETSTRING_LENGTH_DEC: usize = 10;
+#[cfg(feature = "tokio")]
+mod macros;
 
-async fn tag<T: AsyncRead + Unpin + ?Sized>(expected: u8, reader: &mut T) -> io::Result<()> {
-    let received = reader.read_u8().await?;
-    if expected != received {
-        Err(ErrorKind::InvalidData.into())
-    } else {
-        Ok(())
-    }
-}
-
-async fn read_netstring_length<T: AsyncRead + Unpin + ?Sized>(reader: &mut T) -> io::Result<usize> {
-    let mut buffer = [0u8; MAX_NETSTRING_LENGTH_DEC];
-    let mut read_buffer_len = 0usize;
-
-    for i in buffer.iter_mut() {
-        match reader.read_u8().await? {
-            b':' => break,
-            byte @ (b'0'..=b'9') => {
-                *i = byte;
-                read_buffer_len += 1;
-            }
-            _ => return Err(ErrorKind::InvalidData.into()),
-        }
-    }
-
-    if read_buffer_len == MAX_NETSTRING_LENGTH_DEC {
-        tag(b':', reader).await?;
-    }
-
-    // SAFETY: The validation was already performed when writing into the buffer
-    // that this is a valid string that contains only numbers.
-    unsafe {
-        Ok(std::str::from_utf8_unchecked(&buffer[..read_buffer_len])
-            .parse()
-            .unwrap())
-    }
-}
-
-#[cfg(err_drop_message)]
-async fn drop_message<T: AsyncRead + Unpin + ?Sized>(
-    reader: &mut T,
-    mut size: usize,
-) -> io::Result<usize> {
-    const INTERN_BUFFER_SIZE: usize = 4096;
-
-    let mut intern_buffer = [0u8; INTERN_BUFFER_SIZE];
-    while size > INTERN_BUFFER_SIZE {
-        size -= reader.read_exact(&mut intern_buffer).await?;
-    }
-    reader.read_exact(&mut intern_buffer[..size]).await?;
+#[cfg(feature = "std")]
+mod blocking;
+#[cfg(feature = "tokio")]
+mod body;
+#[cfg(feature = "codec")]
+mod codec;
+#[cfg(feature = "tokio")]
+mod copy;
+#[cfg(feature = "serde")]
+mod de;
+mod decoder;
+#[cfg(feature = "tokio")]
+mod drop;
+mod encoder;
+mod error;
+#[cfg(feature = "std")]
+mod frame;
+#[cfg(feature = "std")]
+mod instrument;
+mod list;
+#[cfg(feature = "std")]
+mod logging;
+#[cfg(feature = "std")]
+mod metrics;
+mod options;
+#[cfg(feature = "tokio")]
+mod poll;
+#[cfg(feature = "tokio")]
+mod read;
+#[cfg(feature = "tokio")]
+mod read_alloc;
+#[cfg(feature = "tokio")]
+mod reader;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "stream")]
+mod sink;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "tokio")]
+mod try_read;
+#[cfg(feature = "tokio")]
+mod write;
+#[cfg(feature = "tokio")]
+mod write_reader;
 
-    Err(ErrorKind::BrokenPipe.into())
-}
+#[cfg(feature = "std")]
+pub use crate::blocking::{NetstringRead, NetstringWrite};
+#[cfg(feature = "tokio")]
+pub use crate::body::{FinishBody, NetstringBody, ReadBody};
+#[cfg(feature = "codec")]
+pub use crate::codec::NetstringCodec;
+#[cfg(feature = "tokio")]
+pub use crate::copy::{copy_netstring, relay_netstrings, CopyMessage, RelayFrame, RelayNetstrings};
+#[cfg(feature = "serde")]
+pub use crate::de::from_netstring;
+pub use crate::decoder::{DecodeEvent, NetstringDecoder};
+#[cfg(feature = "tokio")]
+pub use crate::drop::DropMessage;
+#[cfg(feature = "alloc")]
+pub use crate::encoder::encode_to_vec;
+pub use crate::encoder::{encode_header, encode_to_slice, encoded_len, MAX_HEADER_LENGTH};
+#[cfg(feature = "std")]
+pub use crate::error::BufferTooSmall;
+pub use crate::error::NetstringError;
+#[cfg(feature = "serde")]
+pub use crate::error::SerdeError;
+#[cfg(feature = "alloc")]
+pub use crate::list::encode_list_to_vec;
+pub use crate::list::{decode_list, encode_list_to_slice, encoded_list_len, NetstringList};
+#[cfg(feature = "std")]
+pub use crate::logging::{payload_logging, set_payload_logging, PayloadLogging};
+#[cfg(feature = "std")]
+pub use crate::metrics::{
+    set_metrics, AtomicMetrics, MetricsSnapshot, NetstringMetrics, SetMetricsError,
+};
+pub use crate::options::{NetstringReadOptions, OversizePolicy, Strictness};
+#[cfg(feature = "tokio")]
+pub use crate::read::ReadMessage;
+#[cfg(all(feature = "tokio", feature = "serde"))]
+pub use crate::read_alloc::ReadMessageSerde;
+#[cfg(feature = "tokio")]
+pub use crate::read_alloc::{ReadMessageAlloc, ReadMessageList};
+#[cfg(feature = "tokio")]
+pub use crate::reader::{NetstringReader, NextFrame};
+#[cfg(feature = "serde")]
+pub use crate::ser::to_netstring;
+#[cfg(feature = "stream")]
+pub use crate::sink::NetstringSink;
+#[cfg(feature = "stream")]
+pub use crate::stream::Netstrings;
+#[cfg(feature = "tokio")]
+pub use crate::try_read::{TryReadMessage, TryReadMessageAlloc};
+#[cfg(all(feature = "tokio", feature = "serde"))]
+pub use crate::write::WriteMessageSerde;
+#[cfg(feature = "tokio")]
+pub use crate::write::{WriteMessage, WriteMessageList, WriteMessageParts, WriteMessages};
+#[cfg(feature = "fs")]
+pub use crate::write_reader::WriteFile;
+#[cfg(feature = "tokio")]
+pub use crate::write_reader::WriteMessageFromReader;
 
-#[cfg(not(err_drop_message))]
-async fn drop_message<T: AsyncRead + Unpin + ?Sized>(
-    _reader: &mut T,
-    _size: usize,
-) -> io::Result<usize> {
-    Err(ErrorKind::BrokenPipe.into())
-}
+#[cfg(feature = "tokio")]
+use crate::poll::{poll_buf_read_event, poll_read_event};
+#[cfg(feature = "tokio")]
+use std::io::IoSlice;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite};
 
 /// The `AsyncNetstringRead` trait allows you to read one netstring at a time from any stream
 /// that has `AsyncRead` implemented. No implementation is thread-safe and multiple simultaneous
 /// reads can corrupt the message stream irreparably.
-#[async_trait]
+#[cfg(feature = "tokio")]
 pub trait AsyncNetstringRead: AsyncRead + Unpin {
     /// This method allows to read one netstring into the buffer given. It is advised to use
     /// this Trait on a [tokio::io::BufReader] to avoid repeated system calls during parsing.
     ///
     /// # Usage
     /// ```no_exec
-    /// use tokio_netstring::NetstringReader;
+    /// use tokio_netstring_trait::AsyncNetstringRead;
     ///
     /// let buf = [0; 1024];
     /// let len: usize = stream.read_netstring(&mut buf).await.unwrap();
@@ -99,47 +166,116 @@ pub trait AsyncNetstringRead: AsyncRead + Unpin {
     ///
     /// # Errors
     /// This method returns a `tokio::io::Result` which is a re-export from `std::io::Result`.
+    /// All errors caused by the peer wrap a [NetstringError], which tells the exact cause:
+    ///
+    /// ```no_exec
+    /// use tokio_netstring_trait::NetstringError;
+    ///
+    /// match err.get_ref().and_then(|err| err.downcast_ref::<NetstringError>()) {
+    ///     Some(NetstringError::BadTerminator(byte)) => warn!("bad terminator {}", byte),
+    ///     ...
+    /// }
+    /// ```
     ///
     /// ## ErrorKind::UnexpectedEof
     /// This error kind is returned, if the stream got closed, before a Netstring could be fully read.
+    /// It wraps [NetstringError::EofInHeader] or [NetstringError::EofInBody].
     ///
-    /// ## ErrorKind::BrokenPipe
+    /// ## ErrorKind::InvalidInput
     /// This error type indicates that the buffer provided is to small for the netstring to fit in.
-    /// In the current implementation this error is irrecoverable as it has corrupted the stream.
-    /// Future implementations may allow to recover from this.
+    /// The error wraps a [BufferTooSmall], which holds the length announced by the header. The
+    /// stream is left right after the header, so the netstring can be read with a larger buffer
+    /// by [AsyncNetstringRead::resume_netstring].
     ///
-    /// Is the feature `err_drop_message` set, then the netstring will be dropped. Therefor is the
-    /// stream afterwards in a known stream an can be further used.
+    /// ```no_exec
+    /// use tokio_netstring_trait::{AsyncNetstringRead, BufferTooSmall};
+    ///
+    /// let mut buf = vec![0; 1024];
+    /// let len = match stream.read_netstring(&mut buf).await {
+    ///     Err(err) => match err.get_ref().and_then(|err| err.downcast_ref::<BufferTooSmall>()) {
+    ///         Some(too_small) => {
+    ///             buf = vec![0; too_small.len];
+    ///             stream.resume_netstring(&mut buf, too_small.len).await?
+    ///         }
+    ///         None => return Err(err),
+    ///     },
+    ///     len => len?,
+    /// };
+    /// ```
+    ///
+    /// Other ways to handle such netstrings can be chosen with [NetstringReadOptions::oversize].
     ///
     /// ## ErrorKind::InvalidData
-    /// This error can be returned on three occasions:
+    /// This error can be returned on four occasions:
     ///
     /// 1. The size provided is to big. The length of the netstring is stored as a `usize`. Should
-    /// the message provide a longer value, it is most likely an error and will be returned as such.
+    ///    the message provide a longer value, it is most likely an error and will be returned as such
+    ///    ([NetstringError::LengthOverflow]).
     ///
-    /// 1. The Separator between length and the netstring is not `b':'`.
+    /// 1. The length header contains something else than digits
+    ///    ([NetstringError::InvalidLengthDigit]).
     ///
-    /// 1. The Netstring does not end with a `b','`.
+    /// 1. The Separator between length and the netstring is not `b':'`
+    ///    ([NetstringError::BadSeparator]).
+    ///
+    /// 1. The Netstring does not end with a `b','` ([NetstringError::BadTerminator]).
     ///
     /// In all cases the stream is irreparably corrupted and the connection should therefor be dropped.
-    async fn read_netstring(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
-        let length = read_netstring_length(self).await?;
-
-        if buffer.len() >= length {
-            self.read_exact(&mut buffer[..length]).await?;
-        } else {
-            return drop_message(self, length).await;
-        }
-
-        trace!(
-            "READING NETSTRING: {}:{},",
-            length,
-            std::str::from_utf8(&buffer[..length]).unwrap()
-        );
+    fn read_netstring<'a>(&'a mut self, buffer: &'a mut [u8]) -> ReadMessage<'a, Self> {
+        read::read_netstring(
+            self,
+            buffer,
+            NetstringReadOptions::default(),
+            poll_read_event,
+        )
+    }
 
-        tag(b',', self).await?;
+    /// This method works like [AsyncNetstringRead::read_netstring], but applies the limits
+    /// set in `options`.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring]. Should the netstring
+    /// be longer than [NetstringReadOptions::max_len], it fails with `ErrorKind::InvalidData`
+    /// wrapping [NetstringError::TooLarge] right after the length header has been read. Should it be longer than the buffer, it is
+    /// handled according to [NetstringReadOptions::oversize].
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::{AsyncNetstringRead, NetstringReadOptions, OversizePolicy};
+    ///
+    /// let options = NetstringReadOptions {
+    ///     oversize: OversizePolicy::Truncate,
+    ///     ..Default::default()
+    /// };
+    /// let mut buf = [0; 1024];
+    /// let len = stream.read_netstring_with(&mut buf, options).await?;
+    /// let buf: &[u8] = &buf[..len.min(buf.len())];
+    /// ```
+    fn read_netstring_with<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+        options: NetstringReadOptions,
+    ) -> ReadMessage<'a, Self> {
+        read::read_netstring(self, buffer, options, poll_read_event)
+    }
 
-        return Ok(length);
+    /// This method continues to read a netstring of length `len`, after
+    /// [AsyncNetstringRead::read_netstring] failed with a [BufferTooSmall] error. It reads the
+    /// payload and the terminator into the buffer given and returns the length of the payload.
+    ///
+    /// It must only be called right after such an error, as the header is expected to have been
+    /// consumed already.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring]. Should the buffer
+    /// still be to small, the same [BufferTooSmall] error is returned again, without reading
+    /// from the stream.
+    fn resume_netstring<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+        len: usize,
+    ) -> ReadMessage<'a, Self> {
+        read::resume_netstring(self, buffer, len)
     }
 
     /// This method allows to read one netstring. It returns the netstring as a `Vec<u8>` and
@@ -150,45 +286,330 @@ pub trait AsyncNetstringRead: AsyncRead + Unpin {
     ///
     /// # Usage
     /// ```no_exec
-    /// use tokio_netstring::NetstringReader;
+    /// use tokio_netstring_trait::AsyncNetstringRead;
     ///
-    /// let netstring: Vec<u8> = stream.read_netstring_alloc(&mut buf).await.unwrap();
+    /// let netstring: Vec<u8> = stream.read_netstring_alloc().await.unwrap();
     /// ```
     ///
     /// # Errors
     /// It returns the same errors as [AsyncNetstringRead::read_netstring], but can't fail because
     /// the buffer is to small.
     ///
-    async fn read_netstring_alloc(&mut self) -> io::Result<Vec<u8>> {
-        let length = read_netstring_length(self).await?;
-        let mut buffer = Vec::with_capacity(length);
+    fn read_netstring_alloc(&mut self) -> ReadMessageAlloc<'_, Self> {
+        read_alloc::read_netstring_alloc(self, NetstringReadOptions::default(), poll_read_event)
+    }
+
+    /// This method works like [AsyncNetstringRead::read_netstring_alloc], but applies the limits
+    /// set in `options`. With a sensible [NetstringReadOptions::max_len], it is safe to use in
+    /// production, as no memory is allocated for netstrings longer than that.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::{AsyncNetstringRead, NetstringReadOptions};
+    ///
+    /// let options = NetstringReadOptions {
+    ///     max_len: 4096,
+    ///     ..Default::default()
+    /// };
+    /// let netstring: Vec<u8> = stream.read_netstring_alloc_with(options).await.unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_with].
+    fn read_netstring_alloc_with(
+        &mut self,
+        options: NetstringReadOptions,
+    ) -> ReadMessageAlloc<'_, Self> {
+        read_alloc::read_netstring_alloc(self, options, poll_read_event)
+    }
+
+    /// This method reads one netstring, whose payload nests netstrings, e.g. the headers of
+    /// SCGI or the messages of QMQP, and returns the nested payloads. The nested netstrings must
+    /// consume the payload exactly.
+    ///
+    /// It allocates the memory like [AsyncNetstringRead::read_netstring_alloc], so
+    /// [AsyncNetstringRead::read_netstring_list_with] should be preferred in production.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringRead;
+    ///
+    /// let items: Vec<Vec<u8>> = stream.read_netstring_list().await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_alloc]. Should the
+    /// nested netstrings be invalid or not match the payload, an error of kind
+    /// `ErrorKind::InvalidData` is returned, which wraps the [NetstringError].
+    fn read_netstring_list(&mut self) -> ReadMessageList<'_, Self> {
+        self.read_netstring_list_with(NetstringReadOptions::default())
+    }
+
+    /// This method works like [AsyncNetstringRead::read_netstring_list], but applies the limits
+    /// set in `options` to the outer netstring.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_list].
+    fn read_netstring_list_with(
+        &mut self,
+        options: NetstringReadOptions,
+    ) -> ReadMessageList<'_, Self> {
+        read_alloc::read_netstring_list(self.read_netstring_alloc_with(options))
+    }
+
+    /// This method reads one netstring and deserializes a value from it like [from_netstring].
+    ///
+    /// It allocates the memory like [AsyncNetstringRead::read_netstring_alloc], so
+    /// [AsyncNetstringRead::read_netstring_serde_with] should be preferred in production.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringRead;
+    ///
+    /// let request: Request = stream.read_netstring_serde().await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_alloc]. Should the value
+    /// not be deserialized, an error of kind `ErrorKind::InvalidData` is returned, which wraps
+    /// the [SerdeError].
+    #[cfg(feature = "serde")]
+    fn read_netstring_serde<T>(&mut self) -> ReadMessageSerde<'_, Self, T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.read_netstring_serde_with(NetstringReadOptions::default())
+    }
 
-        // SAFETY: The buffer has capacity `length` therefore indexing it until there is safe.
-        let buffer_slice = unsafe { buffer.get_unchecked_mut(..length) };
+    /// This method works like [AsyncNetstringRead::read_netstring_serde], but applies the limits
+    /// set in `options` to the outer netstring.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_serde].
+    #[cfg(feature = "serde")]
+    fn read_netstring_serde_with<T>(
+        &mut self,
+        options: NetstringReadOptions,
+    ) -> ReadMessageSerde<'_, Self, T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        read_alloc::read_netstring_serde(self.read_netstring_alloc_with(options))
+    }
+
+    /// This method works like [AsyncNetstringRead::read_netstring], but distinguishes a peer
+    /// that closed the stream after its last netstring from one that closed it in the middle of
+    /// a netstring. Should the stream end before the first byte of the length header, `None` is
+    /// returned.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringRead;
+    ///
+    /// let mut buf = [0; 1024];
+    /// while let Some(len) = stream.try_read_netstring(&mut buf).await? {
+    ///     let buf: &[u8] = &buf[..len];
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring]. `ErrorKind::UnexpectedEof`
+    /// is only returned, if the stream ended inside the header, the payload or before the
+    /// terminator.
+    fn try_read_netstring<'a>(&'a mut self, buffer: &'a mut [u8]) -> TryReadMessage<'a, Self> {
+        try_read::try_read_netstring(self.read_netstring(buffer))
+    }
 
-        self.read_exact(buffer_slice).await?;
+    /// This method works like [AsyncNetstringRead::try_read_netstring], but applies the limits
+    /// set in `options`.
+    fn try_read_netstring_with<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+        options: NetstringReadOptions,
+    ) -> TryReadMessage<'a, Self> {
+        try_read::try_read_netstring(self.read_netstring_with(buffer, options))
+    }
 
-        tag(b',', self).await?;
+    /// This method works like [AsyncNetstringRead::read_netstring_alloc], but returns `None` if
+    /// the stream ends before the first byte of the length header, like
+    /// [AsyncNetstringRead::try_read_netstring].
+    fn try_read_netstring_alloc(&mut self) -> TryReadMessageAlloc<'_, Self> {
+        try_read::try_read_netstring_alloc(self.read_netstring_alloc())
+    }
 
-        // SAFETY: We have read all the bytes from the source into the Vec. At that point all
-        // values up until length have to be initialized.
-        unsafe { buffer.set_len(length) };
+    /// This method works like [AsyncNetstringRead::try_read_netstring_alloc], but applies the
+    /// limits set in `options`.
+    fn try_read_netstring_alloc_with(
+        &mut self,
+        options: NetstringReadOptions,
+    ) -> TryReadMessageAlloc<'_, Self> {
+        try_read::try_read_netstring_alloc(self.read_netstring_alloc_with(options))
+    }
 
-        return Ok(buffer);
+    /// This method reads one netstring and discards its content. It can be used to skip messages
+    /// that are of no interest without having to provide a buffer for them.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringRead;
+    ///
+    /// stream.drop_netstring().await.unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_alloc].
+    ///
+    fn drop_netstring(&mut self) -> DropMessage<'_, Self> {
+        drop::drop_netstring(self)
+    }
+
+    /// This method reads the length header of a netstring and returns its payload as an
+    /// `AsyncRead`, which ends after the announced length. It allows to copy large payloads,
+    /// e.g. to a file with `tokio::io::copy`, without holding them in memory.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringRead;
+    ///
+    /// let mut body = stream.read_netstring_body().await?;
+    /// let mut file = File::create(path).await?;
+    /// tokio::io::copy(&mut body, &mut file).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// The future and the body return the same errors as [AsyncNetstringRead::read_netstring_alloc].
+    /// The terminator is verified by the body before it reports EOF.
+    ///
+    /// Should the body be dropped before it reported EOF and without awaiting
+    /// [NetstringBody::finish], the rest of the payload and the terminator are left in the stream.
+    /// The stream is unusable afterwards, as the next read starts in the middle of the payload.
+    fn read_netstring_body(&mut self) -> ReadBody<'_, Self> {
+        body::read_netstring_body(self)
+    }
+
+    /// This method turns the reader into a stream of netstrings, like
+    /// `tokio::io::AsyncBufReadExt::lines` does for lines. Every netstring is allocated as by
+    /// [AsyncNetstringRead::read_netstring_alloc].
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use futures::StreamExt;
+    /// use tokio_netstring_trait::AsyncNetstringRead;
+    ///
+    /// let mut netstrings = stream.netstrings();
+    /// while let Some(netstring) = netstrings.next().await {
+    ///     let netstring: Vec<u8> = netstring?;
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// The stream yields the same errors as [AsyncNetstringRead::read_netstring_alloc], except
+    /// that EOF between two netstrings ends the stream instead of returning an error.
+    #[cfg(feature = "stream")]
+    fn netstrings(self) -> Netstrings<Self>
+    where
+        Self: Sized,
+    {
+        stream::netstrings(self, NetstringReadOptions::default())
+    }
+
+    /// This method works like [AsyncNetstringRead::netstrings], but applies the limits set in
+    /// `options` to every netstring.
+    #[cfg(feature = "stream")]
+    fn netstrings_with(self, options: NetstringReadOptions) -> Netstrings<Self>
+    where
+        Self: Sized,
+    {
+        stream::netstrings(self, options)
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<Reader: AsyncRead + Unpin + ?Sized> AsyncNetstringRead for Reader {}
 
-/// The `NetstringWriter` trait allows to write a slice of bytes as a netstring to any stream that
+/// The `AsyncNetstringBufRead` trait reads netstrings from any stream that has `AsyncBufRead`
+/// implemented, like `tokio::io::BufReader`. Instead of reading the length header and the
+/// terminator one byte at a time, they are scanned straight out of the buffer of the reader and
+/// the payload is copied from it, which avoids a call to `poll_read` per byte.
+///
+/// The methods are named differently from the ones of [AsyncNetstringRead], as every
+/// `AsyncBufRead` implements `AsyncRead` as well. Both can be mixed on the same stream.
+#[cfg(feature = "tokio")]
+pub trait AsyncNetstringBufRead: AsyncBufRead + Unpin {
+    /// This method works like [AsyncNetstringRead::read_netstring], but reads from the buffer of
+    /// the reader.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio::io::BufReader;
+    /// use tokio_netstring_trait::AsyncNetstringBufRead;
+    ///
+    /// let mut stream = BufReader::new(stream);
+    /// let mut buf = [0; 1024];
+    /// let len: usize = stream.read_netstring_buffered(&mut buf).await?;
+    /// let buf: &[u8] = &buf[..len];
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring].
+    fn read_netstring_buffered<'a>(&'a mut self, buffer: &'a mut [u8]) -> ReadMessage<'a, Self> {
+        read::read_netstring(
+            self,
+            buffer,
+            NetstringReadOptions::default(),
+            poll_buf_read_event,
+        )
+    }
+
+    /// This method works like [AsyncNetstringBufRead::read_netstring_buffered], but applies the
+    /// limits set in `options`.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_with].
+    fn read_netstring_buffered_with<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+        options: NetstringReadOptions,
+    ) -> ReadMessage<'a, Self> {
+        read::read_netstring(self, buffer, options, poll_buf_read_event)
+    }
+
+    /// This method works like [AsyncNetstringRead::read_netstring_alloc], but reads from the
+    /// buffer of the reader.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_alloc].
+    fn read_netstring_alloc_buffered(&mut self) -> ReadMessageAlloc<'_, Self> {
+        read_alloc::read_netstring_alloc(self, NetstringReadOptions::default(), poll_buf_read_event)
+    }
+
+    /// This method works like [AsyncNetstringBufRead::read_netstring_alloc_buffered], but
+    /// applies the limits set in `options`.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringRead::read_netstring_alloc_with].
+    fn read_netstring_alloc_buffered_with(
+        &mut self,
+        options: NetstringReadOptions,
+    ) -> ReadMessageAlloc<'_, Self> {
+        read_alloc::read_netstring_alloc(self, options, poll_buf_read_event)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<Reader: AsyncBufRead + Unpin + ?Sized> AsyncNetstringBufRead for Reader {}
+
+/// The `AsyncNetstringWrite` trait allows to write a slice of bytes as a netstring to any stream that
 /// implements `AsyncWrite`
-#[async_trait]
+#[cfg(feature = "tokio")]
 pub trait AsyncNetstringWrite: AsyncWrite + Unpin {
-    /// Write the slice as a netstring to the stream.
+    /// Write the slice as a netstring to the stream and flush it. Should the stream support
+    /// vectored writes, the header, the payload and the terminator are written with one call
+    /// without copying the payload.
     ///
     /// # Usage
     /// ```no_exec
-    /// use tokio_netstring::NetstringWriter;
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
     ///
     /// let msg = "Hello, World!";
     /// stream.write_netstring(&msg.as_bytes());
@@ -198,26 +619,182 @@ pub trait AsyncNetstringWrite: AsyncWrite + Unpin {
     /// This method returns a `tokio::io::Result` which is a re-export from `std::io::Result`. It
     /// returns `ErrorKind::WriteZero` if the stream was closed an no more data can be sent.
     ///
-    async fn write_netstring(&mut self, data: &[u8]) -> io::Result<()> {
-        let mut buffer = [0u8; 2 * MAX_NETSTRING_LENGTH_DEC + 1];
-        let len = {
-            let mut writer = Cursor::new(&mut buffer[..]);
-            write!(writer, "{}", data.len())?;
-            writer.position() as usize
-        };
-        buffer[len] = b':';
-
-        trace!(
-            "WRITING NETSTRING: {}{},",
-            std::str::from_utf8(&buffer[..len + 1]).unwrap(),
-            std::str::from_utf8(data).unwrap()
-        );
-
-        self.write_all(&buffer[..len + 1]).await?;
-        self.write_all(data).await?;
-        self.write_all(b",").await?;
-        self.flush().await
+    fn write_netstring<'a>(&'a mut self, data: &'a [u8]) -> WriteMessage<'a, Self> {
+        write::write_netstring(self, data, true)
+    }
+
+    /// This method works like [AsyncNetstringWrite::write_netstring], but does not flush the
+    /// stream afterwards. It allows to write several netstrings to a buffered stream, like
+    /// `tokio::io::BufWriter`, and flush them at once.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio::io::{AsyncWriteExt, BufWriter};
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
+    ///
+    /// let mut stream = BufWriter::new(stream);
+    /// stream.write_netstring_no_flush(b"Hello").await?;
+    /// stream.write_netstring_no_flush(b"World!").await?;
+    /// stream.flush().await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring].
+    fn write_netstring_no_flush<'a>(&'a mut self, data: &'a [u8]) -> WriteMessage<'a, Self> {
+        write::write_netstring(self, data, false)
+    }
+
+    /// This method writes one netstring, whose payload consists of all `parts` in order, and
+    /// flushes the stream afterwards. It avoids concatenating the parts, as the length header
+    /// is computed from all of them.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
+    ///
+    /// let header: &[u8] = b"GET";
+    /// let body: &[u8] = b" /index.html";
+    /// stream.write_netstring_parts(&[header, body]).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring].
+    fn write_netstring_parts<'a>(
+        &'a mut self,
+        parts: &'a [&'a [u8]],
+    ) -> WriteMessageParts<'a, Self, &'a [u8]> {
+        write::write_netstring_parts(self, parts)
+    }
+
+    /// This method works like [AsyncNetstringWrite::write_netstring_parts], but takes the parts
+    /// as `IoSlice`s.
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring].
+    fn write_netstring_vectored<'a>(
+        &'a mut self,
+        parts: &'a [IoSlice<'a>],
+    ) -> WriteMessageParts<'a, Self, IoSlice<'a>> {
+        write::write_netstring_parts(self, parts)
+    }
+
+    /// This method writes one netstring, whose payload is the concatenation of every item of
+    /// `items` framed as a netstring, and flushes the stream afterwards. It is the counterpart of
+    /// [AsyncNetstringRead::read_netstring_list].
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
+    ///
+    /// let items: [&[u8]; 2] = [b"CONTENT_LENGTH", b"0"];
+    /// stream.write_netstring_list(&items).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring].
+    fn write_netstring_list(&mut self, items: &[&[u8]]) -> WriteMessageList<'_, Self> {
+        write::write_netstring_list(self, items)
+    }
+
+    /// This method serializes `value` like [to_netstring], writes it and flushes the stream
+    /// afterwards. It is the counterpart of [AsyncNetstringRead::read_netstring_serde].
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
+    ///
+    /// stream.write_netstring_serde(&request).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring]. Should the value not
+    /// be serialized, an error of kind `ErrorKind::InvalidData` is returned, which wraps the
+    /// [SerdeError].
+    #[cfg(feature = "serde")]
+    fn write_netstring_serde<T>(&mut self, value: &T) -> WriteMessageSerde<'_, Self>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        write::write_netstring_serde(self, value)
+    }
+
+    /// This method writes one netstring of length `len`, whose payload is copied from `reader`,
+    /// and flushes the stream afterwards. It allows to send payloads, that don't fit into
+    /// memory, as only a small buffer is used to copy them.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
+    ///
+    /// let (len, mut body) = open_upload().await?;
+    /// stream.write_netstring_from_reader(len, &mut body).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring]. Should `reader` end
+    /// before `len` bytes are copied, it fails with `ErrorKind::UnexpectedEof`. Should it hold
+    /// more than `len` bytes, it fails with `ErrorKind::InvalidInput`. In both cases the
+    /// terminator is not written and the stream should be closed.
+    fn write_netstring_from_reader<'a, R>(
+        &'a mut self,
+        len: usize,
+        reader: &'a mut R,
+    ) -> WriteMessageFromReader<'a, Self, R>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        write_reader::write_netstring_from_reader(self, len, reader)
+    }
+
+    /// This method writes the content of `file` as one netstring, like
+    /// [AsyncNetstringWrite::write_netstring_from_reader], with the length taken from the
+    /// metadata of the file. The file is read from its current position, so it should be at
+    /// the start.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio::fs::File;
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
+    ///
+    /// let mut file = File::open("backup.tar").await?;
+    /// stream.write_netstring_from_file(&mut file).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring_from_reader] and
+    /// the errors of reading the metadata.
+    #[cfg(feature = "fs")]
+    fn write_netstring_from_file<'a>(
+        &'a mut self,
+        file: &'a mut tokio::fs::File,
+    ) -> WriteFile<'a, Self> {
+        write_reader::write_netstring_from_file(self, file)
+    }
+
+    /// This method writes every item of `netstrings` as a netstring to the stream and flushes
+    /// it once after all of them are written.
+    ///
+    /// # Usage
+    /// ```no_exec
+    /// use tokio_netstring_trait::AsyncNetstringWrite;
+    ///
+    /// let messages = vec!["Hello", "World!"];
+    /// stream.write_netstrings(messages).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// It returns the same errors as [AsyncNetstringWrite::write_netstring]. Should an error
+    /// occur, the netstrings before it have been written completely and the remaining ones are
+    /// not written at all, but the failing one may have been written partially. The stream is
+    /// left in the middle of a netstring then and can't be reused.
+    fn write_netstrings<I>(&mut self, netstrings: I) -> WriteMessages<'_, Self, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        write::write_netstrings(self, netstrings.into_iter())
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<Writer: AsyncWrite + Unpin + ?Sized> AsyncNetstringWrite for Writer {}
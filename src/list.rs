@@ -0,0 +1,126 @@
+use crate::decoder::{DecodeEvent, NetstringDecoder};
+use crate::encoder::{encode_header, encode_to_slice, encoded_len, MAX_HEADER_LENGTH};
+use crate::error::NetstringError;
+
+/// Returns the length of the netstring, that nests every item of `items` as a netstring in its
+/// payload.
+pub fn encoded_list_len(items: &[&[u8]]) -> usize {
+    encoded_len(payload_len(items))
+}
+
+fn payload_len(items: &[&[u8]]) -> usize {
+    items.iter().map(|item| encoded_len(item.len())).sum()
+}
+
+/// Writes `items` as one netstring, whose payload is the concatenation of every item framed as
+/// a netstring, into `buf` and returns the number of bytes written. Should `buf` be shorter than
+/// [encoded_list_len] of the items, `None` is returned and `buf` is left untouched.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::encode_list_to_slice;
+///
+/// let mut buf = [0; 32];
+/// let items: [&[u8]; 2] = [b"Hello", b"World!"];
+/// let len = encode_list_to_slice(&items, &mut buf).unwrap();
+/// assert_eq!(b"17:5:Hello,6:World!,,", &buf[..len]);
+/// ```
+pub fn encode_list_to_slice(items: &[&[u8]], buf: &mut [u8]) -> Option<usize> {
+    let len = encoded_list_len(items);
+    if buf.len() < len {
+        return None;
+    }
+
+    let mut header = [0; MAX_HEADER_LENGTH];
+    let header = encode_header(payload_len(items), &mut header);
+    buf[..header.len()].copy_from_slice(header);
+
+    let mut pos = header.len();
+    for item in items {
+        pos += encode_to_slice(item, &mut buf[pos..])?;
+    }
+    buf[pos] = b',';
+
+    Some(len)
+}
+
+/// Appends `items` as one netstring, whose payload is the concatenation of every item framed as
+/// a netstring, to `buf`.
+#[cfg(feature = "alloc")]
+pub fn encode_list_to_vec(items: &[&[u8]], buf: &mut alloc::vec::Vec<u8>) {
+    let start = buf.len();
+    buf.resize(start + encoded_list_len(items), 0);
+    encode_list_to_slice(items, &mut buf[start..]).expect("the buffer has been resized to fit");
+}
+
+/// Decodes the payload of a netstring, that nests netstrings, into an iterator over the nested
+/// payloads.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::decode_list;
+///
+/// let items = decode_list(b"5:Hello,6:World!,").collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(vec![&b"Hello"[..], &b"World!"[..]], items);
+/// ```
+///
+/// # Errors
+/// The nested netstrings must consume the payload exactly. Should one of them be invalid, the
+/// iterator yields the error and ends afterwards. Should the payload end in the middle of a
+/// nested netstring, `NetstringError::EofInHeader` or `NetstringError::EofInBody` is yielded.
+pub fn decode_list(payload: &[u8]) -> NetstringList<'_> {
+    NetstringList {
+        input: payload,
+        decoder: NetstringDecoder::new(),
+        done: false,
+    }
+}
+
+/// An iterator over the netstrings nested in the payload of a netstring, created by
+/// [decode_list].
+#[derive(Debug, Clone)]
+pub struct NetstringList<'a> {
+    input: &'a [u8],
+    decoder: NetstringDecoder,
+    done: bool,
+}
+
+impl<'a> NetstringList<'a> {
+    /// Returns the part of the payload, that has not been decoded yet.
+    #[cfg(feature = "serde")]
+    pub(crate) fn rest(&self) -> &'a [u8] {
+        self.input
+    }
+}
+
+impl<'a> Iterator for NetstringList<'a> {
+    type Item = Result<&'a [u8], NetstringError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.input.is_empty() {
+            return None;
+        }
+
+        let mut item: &'a [u8] = &[];
+        loop {
+            if self.input.is_empty() {
+                self.done = true;
+                return Some(Err(self.decoder.eof_error()));
+            }
+
+            let input = self.input;
+            let (consumed, event) = self.decoder.decode(input);
+            self.input = &input[consumed..];
+
+            match event {
+                None | Some(DecodeEvent::Length(_)) => {}
+                Some(DecodeEvent::Body(chunk)) => item = chunk,
+                Some(DecodeEvent::Complete) => return Some(Ok(item)),
+                Some(DecodeEvent::Error(err)) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
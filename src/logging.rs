@@ -0,0 +1,173 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::{log_enabled, trace, Level};
+
+/// How the payload of a netstring is shown, when reads, writes and drops are logged at the
+/// `trace` level.
+///
+/// The mode is set for the whole process with [set_payload_logging]. Payloads are never
+/// assumed to be UTF-8, so logging can't fail on binary data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadLogging {
+    /// Don't log netstrings at all.
+    Off,
+    /// Log the length of every netstring, but not its payload.
+    #[default]
+    LengthOnly,
+    /// Log the payload with non-printable bytes escaped like `\x00`, cut off after `max_bytes`.
+    Escaped {
+        /// The number of payload bytes shown at most.
+        max_bytes: usize,
+    },
+    /// Log the payload as hex, cut off after `max_bytes`.
+    Hex {
+        /// The number of payload bytes shown at most.
+        max_bytes: usize,
+    },
+    /// Log that a netstring was read, written or dropped, but neither its length nor its
+    /// payload.
+    Redacted,
+}
+
+const OFF: usize = 0;
+const LENGTH_ONLY: usize = 1;
+const ESCAPED: usize = 2;
+const HEX: usize = 3;
+const REDACTED: usize = 4;
+
+// The mode is kept in the low bits and `max_bytes` above them, so both are updated together.
+const MODE_BITS: u32 = 3;
+const MODE_MASK: usize = (1 << MODE_BITS) - 1;
+
+static SETTING: AtomicUsize = AtomicUsize::new(LENGTH_ONLY);
+
+/// Sets how the payload of netstrings is logged by all readers and writers of this crate.
+/// `max_bytes` is capped at `usize::MAX >> 3`, which no payload shown can reach anyway.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::{set_payload_logging, PayloadLogging};
+///
+/// set_payload_logging(PayloadLogging::Hex { max_bytes: 64 });
+/// ```
+pub fn set_payload_logging(mode: PayloadLogging) {
+    let (mode, max_bytes) = match mode {
+        PayloadLogging::Off => (OFF, 0),
+        PayloadLogging::LengthOnly => (LENGTH_ONLY, 0),
+        PayloadLogging::Escaped { max_bytes } => (ESCAPED, max_bytes),
+        PayloadLogging::Hex { max_bytes } => (HEX, max_bytes),
+        PayloadLogging::Redacted => (REDACTED, 0),
+    };
+    let max_bytes = max_bytes.min(usize::MAX >> MODE_BITS);
+    SETTING.store((max_bytes << MODE_BITS) | mode, Ordering::Relaxed);
+}
+
+/// Returns how the payload of netstrings is logged, see [set_payload_logging].
+pub fn payload_logging() -> PayloadLogging {
+    let setting = SETTING.load(Ordering::Relaxed);
+    let max_bytes = setting >> MODE_BITS;
+    match setting & MODE_MASK {
+        OFF => PayloadLogging::Off,
+        ESCAPED => PayloadLogging::Escaped { max_bytes },
+        HEX => PayloadLogging::Hex { max_bytes },
+        REDACTED => PayloadLogging::Redacted,
+        _ => PayloadLogging::LengthOnly,
+    }
+}
+
+pub(crate) const READING: &str = "READING NETSTRING";
+pub(crate) const WRITING: &str = "WRITING NETSTRING";
+pub(crate) const DROPPING: &str = "DROPPING NETSTRING";
+#[cfg(feature = "tokio")]
+pub(crate) const COPYING: &str = "COPYING NETSTRING";
+
+/// Logs a netstring, whose payload is available.
+pub(crate) fn log_netstring(action: &str, payload: &[u8]) {
+    log_netstring_parts(action, &[payload]);
+}
+
+/// Logs an encoded netstring, e.g. a list, by its payload.
+pub(crate) fn log_encoded(action: &str, netstring: &[u8]) {
+    let start = netstring
+        .iter()
+        .position(|byte| *byte == b':')
+        .map_or(0, |pos| pos + 1);
+    let end = netstring.len().saturating_sub(1).max(start);
+    log_netstring(action, &netstring[start..end]);
+}
+
+/// Logs a netstring, whose payload consists of `parts`.
+pub(crate) fn log_netstring_parts<T: Deref<Target = [u8]>>(action: &str, parts: &[T]) {
+    if !log_enabled!(Level::Trace) {
+        return;
+    }
+
+    let len = parts.iter().map(|part| part.len()).sum();
+    match payload_logging() {
+        PayloadLogging::Escaped { max_bytes } => {
+            let shown = Shown::new(parts, max_bytes, false);
+            trace!("{}: {}:{},", action, len, shown);
+        }
+        PayloadLogging::Hex { max_bytes } => {
+            let shown = Shown::new(parts, max_bytes, true);
+            trace!("{}: {}:{},", action, len, shown);
+        }
+        _ => log_netstring_len(action, len),
+    }
+}
+
+/// Logs a netstring, whose payload is not available, e.g. because it is streamed.
+pub(crate) fn log_netstring_len(action: &str, len: usize) {
+    match payload_logging() {
+        PayloadLogging::Off => {}
+        PayloadLogging::Redacted => trace!("{}: <redacted>", action),
+        _ => trace!("{}: {} bytes", action, len),
+    }
+}
+
+/// Warns that a [NetstringBody](crate::NetstringBody) has been dropped with `remaining` bytes of
+/// its payload unread. Unlike the netstrings themselves, it is logged in every mode.
+#[cfg(feature = "tokio")]
+pub(crate) fn log_body_dropped(remaining: usize) {
+    log::warn!(
+        "NETSTRING BODY DROPPED: {} bytes of the payload and the terminator were not read",
+        remaining
+    );
+}
+
+/// Shows at most `max_bytes` of the payload, followed by `...` if it has been cut off.
+struct Shown<'a, T> {
+    parts: &'a [T],
+    max_bytes: usize,
+    hex: bool,
+}
+
+impl<'a, T: Deref<Target = [u8]>> Shown<'a, T> {
+    fn new(parts: &'a [T], max_bytes: usize, hex: bool) -> Self {
+        Shown {
+            parts,
+            max_bytes,
+            hex,
+        }
+    }
+}
+
+impl<T: Deref<Target = [u8]>> fmt::Display for Shown<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut bytes = self.parts.iter().flat_map(|part| part.iter().copied());
+
+        for byte in bytes.by_ref().take(self.max_bytes) {
+            match self.hex {
+                true => write!(f, "{:02x}", byte)?,
+                false => write!(f, "{}", std::ascii::escape_default(byte))?,
+            }
+        }
+
+        match bytes.next() {
+            Some(_) => write!(f, "..."),
+            None => Ok(()),
+        }
+    }
+}
@@ -19,20 +19,20 @@ macro_rules! ready_and_ok {
 }
 
 macro_rules! bytes_read {
-    ($e:expr) => {
+    ($e:expr, $decoder:expr) => {
         match $e.filled().len() {
-            0 => return Poll::Ready(Err(eof())),
+            0 => return Poll::Ready(Err(eof($decoder))),
             len => len,
         }
     };
 }
 
 macro_rules! read_byte {
-    ($reader:expr, $cx:expr) => {{
+    ($reader:expr, $decoder:expr, $cx:expr) => {{
         let mut byte_buf = [0; 1];
         let mut read_buf = ReadBuf::new(&mut byte_buf);
         ready_and_ok!(Pin::new(&mut *$reader).poll_read($cx, &mut read_buf));
-        bytes_read!(read_buf);
+        bytes_read!(read_buf, $decoder);
         byte_buf[0]
     }};
 }
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::io::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+#[cfg(feature = "tokio")]
+use crate::encoder::encoded_len;
+use crate::error::{BufferTooSmall, NetstringError};
+use crate::instrument::Direction;
+
+/// Receives the traffic of all readers and writers of this crate, once it is installed with
+/// [set_metrics]. Every method does nothing by default, so an implementation only overrides
+/// what it is interested in.
+///
+/// A netstring forwarded by `copy_netstring` or `relay_netstrings` is reported as both read
+/// and written. The methods are called from the task or thread reading or writing the
+/// netstring, so they should return quickly.
+pub trait NetstringMetrics: Send + Sync {
+    /// A netstring with a payload of `len` bytes has been read.
+    fn frame_read(&self, len: usize) {
+        let _ = len;
+    }
+
+    /// A netstring with a payload of `len` bytes has been written.
+    fn frame_written(&self, len: usize) {
+        let _ = len;
+    }
+
+    /// A netstring with a payload of `len` bytes has been read and discarded.
+    fn frame_dropped(&self, len: usize) {
+        let _ = len;
+    }
+
+    /// `bytes` bytes of a netstring, including the header and the terminator, have been read
+    /// from the stream. A netstring that failed reports the bytes consumed up to the error.
+    fn bytes_read(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// `bytes` bytes of a complete netstring, including the header and the terminator, have
+    /// been written to the stream.
+    fn bytes_written(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// A netstring announcing a payload of `len` bytes has been rejected, either because it
+    /// exceeds [NetstringReadOptions::max_len](crate::NetstringReadOptions::max_len) or
+    /// because it does not fit into the buffer given.
+    fn oversize_rejected(&self, len: usize) {
+        let _ = len;
+    }
+
+    /// A netstring violates the format. [NetstringError::TooLarge] is reported with
+    /// [NetstringMetrics::oversize_rejected] instead.
+    fn protocol_error(&self, err: NetstringError) {
+        let _ = err;
+    }
+}
+
+static METRICS: OnceLock<&'static dyn NetstringMetrics> = OnceLock::new();
+
+/// Installs `metrics` for all readers and writers of this crate. Like `log::set_logger`, it can
+/// only be installed once, as the readers and writers look it up without taking a lock.
+///
+/// # Errors
+/// Returns [SetMetricsError] if metrics have been installed before, in which case they stay in
+/// place.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::{set_metrics, AtomicMetrics};
+///
+/// static METRICS: AtomicMetrics = AtomicMetrics::new();
+///
+/// set_metrics(&METRICS)?;
+/// // ...
+/// let snapshot = METRICS.snapshot();
+/// ```
+pub fn set_metrics(metrics: &'static dyn NetstringMetrics) -> Result<(), SetMetricsError> {
+    METRICS.set(metrics).map_err(|_| SetMetricsError(()))
+}
+
+/// The error returned by [set_metrics] if metrics have already been installed.
+#[derive(Debug)]
+pub struct SetMetricsError(());
+
+impl fmt::Display for SetMetricsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ERROR: Netstring metrics have already been installed")
+    }
+}
+
+impl std::error::Error for SetMetricsError {}
+
+/// Reports the outcome of reading, writing or dropping one netstring, where `res` holds the
+/// length of its payload on success. `bytes` counts the bytes read from the stream, even if the
+/// netstring failed, or the bytes written for [Direction::Write].
+pub(crate) fn record_frame(direction: Direction, res: Result<usize, &Error>, bytes: usize) {
+    let metrics = match METRICS.get() {
+        Some(metrics) => *metrics,
+        None => return,
+    };
+
+    if bytes > 0 {
+        match direction {
+            Direction::Write => metrics.bytes_written(bytes),
+            _ => metrics.bytes_read(bytes),
+        }
+    }
+
+    match (direction, res) {
+        (Direction::Read, Ok(len)) => metrics.frame_read(len),
+        (Direction::Drop, Ok(len)) => metrics.frame_dropped(len),
+        (Direction::Write, Ok(len)) => metrics.frame_written(len),
+        #[cfg(feature = "tokio")]
+        (Direction::Copy, Ok(len)) => {
+            //the netstring is forwarded with a canonical header
+            metrics.frame_read(len);
+            metrics.frame_written(len);
+            metrics.bytes_written(encoded_len(len));
+        }
+        (_, Err(err)) => record_error(metrics, err),
+    }
+}
+
+fn record_error(metrics: &dyn NetstringMetrics, err: &Error) {
+    let err = match err.get_ref() {
+        Some(err) => err,
+        None => return,
+    };
+
+    if let Some(err) = err.downcast_ref::<NetstringError>() {
+        match *err {
+            NetstringError::TooLarge { len, .. } => metrics.oversize_rejected(len),
+            err => metrics.protocol_error(err),
+        }
+    } else if let Some(BufferTooSmall { len }) = err.downcast_ref::<BufferTooSmall>() {
+        metrics.oversize_rejected(*len);
+    }
+}
+
+/// A [NetstringMetrics] implementation counting the traffic with atomic counters, which can be
+/// scraped with [AtomicMetrics::snapshot].
+#[derive(Debug, Default)]
+pub struct AtomicMetrics {
+    frames_read: AtomicU64,
+    frames_written: AtomicU64,
+    frames_dropped: AtomicU64,
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
+    oversize_rejected: AtomicU64,
+    invalid_length_digit: AtomicU64,
+    length_overflow: AtomicU64,
+    leading_zero: AtomicU64,
+    bad_separator: AtomicU64,
+    bad_terminator: AtomicU64,
+    eof_in_header: AtomicU64,
+    eof_in_body: AtomicU64,
+}
+
+impl AtomicMetrics {
+    /// Creates a new `AtomicMetrics` with all counters at zero. It is usable in a `static`.
+    pub const fn new() -> Self {
+        AtomicMetrics {
+            frames_read: AtomicU64::new(0),
+            frames_written: AtomicU64::new(0),
+            frames_dropped: AtomicU64::new(0),
+            bytes_read: AtomicU64::new(0),
+            bytes_written: AtomicU64::new(0),
+            oversize_rejected: AtomicU64::new(0),
+            invalid_length_digit: AtomicU64::new(0),
+            length_overflow: AtomicU64::new(0),
+            leading_zero: AtomicU64::new(0),
+            bad_separator: AtomicU64::new(0),
+            bad_terminator: AtomicU64::new(0),
+            eof_in_header: AtomicU64::new(0),
+            eof_in_body: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the current value of every counter. The counters are read one after another, so
+    /// a snapshot taken during traffic may be off by the frames processed meanwhile.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        MetricsSnapshot {
+            frames_read: load(&self.frames_read),
+            frames_written: load(&self.frames_written),
+            frames_dropped: load(&self.frames_dropped),
+            bytes_read: load(&self.bytes_read),
+            bytes_written: load(&self.bytes_written),
+            oversize_rejected: load(&self.oversize_rejected),
+            invalid_length_digit: load(&self.invalid_length_digit),
+            length_overflow: load(&self.length_overflow),
+            leading_zero: load(&self.leading_zero),
+            bad_separator: load(&self.bad_separator),
+            bad_terminator: load(&self.bad_terminator),
+            eof_in_header: load(&self.eof_in_header),
+            eof_in_body: load(&self.eof_in_body),
+        }
+    }
+}
+
+fn add(counter: &AtomicU64, value: usize) {
+    counter.fetch_add(value as u64, Ordering::Relaxed);
+}
+
+impl NetstringMetrics for AtomicMetrics {
+    fn frame_read(&self, _: usize) {
+        add(&self.frames_read, 1);
+    }
+
+    fn frame_written(&self, _: usize) {
+        add(&self.frames_written, 1);
+    }
+
+    fn frame_dropped(&self, _: usize) {
+        add(&self.frames_dropped, 1);
+    }
+
+    fn bytes_read(&self, bytes: usize) {
+        add(&self.bytes_read, bytes);
+    }
+
+    fn bytes_written(&self, bytes: usize) {
+        add(&self.bytes_written, bytes);
+    }
+
+    fn oversize_rejected(&self, _: usize) {
+        add(&self.oversize_rejected, 1);
+    }
+
+    fn protocol_error(&self, err: NetstringError) {
+        let counter = match err {
+            NetstringError::InvalidLengthDigit(_) => &self.invalid_length_digit,
+            NetstringError::LengthOverflow => &self.length_overflow,
+            NetstringError::LeadingZero => &self.leading_zero,
+            NetstringError::BadSeparator(_) => &self.bad_separator,
+            NetstringError::BadTerminator(_) => &self.bad_terminator,
+            NetstringError::EofInHeader => &self.eof_in_header,
+            NetstringError::EofInBody { .. } => &self.eof_in_body,
+            NetstringError::TooLarge { .. } => &self.oversize_rejected,
+        };
+        add(counter, 1);
+    }
+}
+
+/// The counters of an [AtomicMetrics] at one point in time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MetricsSnapshot {
+    /// The number of netstrings read.
+    pub frames_read: u64,
+    /// The number of netstrings written.
+    pub frames_written: u64,
+    /// The number of netstrings read and discarded.
+    pub frames_dropped: u64,
+    /// The number of bytes read or dropped, including those of netstrings that failed.
+    pub bytes_read: u64,
+    /// The number of bytes of complete netstrings written.
+    pub bytes_written: u64,
+    /// The number of netstrings rejected for their length.
+    pub oversize_rejected: u64,
+    /// The number of [NetstringError::InvalidLengthDigit] errors.
+    pub invalid_length_digit: u64,
+    /// The number of [NetstringError::LengthOverflow] errors.
+    pub length_overflow: u64,
+    /// The number of [NetstringError::LeadingZero] errors.
+    pub leading_zero: u64,
+    /// The number of [NetstringError::BadSeparator] errors.
+    pub bad_separator: u64,
+    /// The number of [NetstringError::BadTerminator] errors.
+    pub bad_terminator: u64,
+    /// The number of [NetstringError::EofInHeader] errors.
+    pub eof_in_header: u64,
+    /// The number of [NetstringError::EofInBody] errors.
+    pub eof_in_body: u64,
+}
@@ -0,0 +1,81 @@
+/// Options that control how netstrings are read.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::{AsyncNetstringRead, NetstringReadOptions};
+///
+/// let options = NetstringReadOptions {
+///     max_len: 4096,
+///     ..Default::default()
+/// };
+/// let netstring: Vec<u8> = stream.read_netstring_alloc_with(options).await?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetstringReadOptions {
+    /// The maximum length of the payload of a netstring. Longer netstrings are rejected as soon
+    /// as their length header is parsed, before any memory is allocated for them.
+    ///
+    /// Defaults to `usize::MAX`, which is unsafe to use with the allocating readers. While they
+    /// only allocate as the payload arrives, the peer can still make them buffer arbitrary
+    /// amounts of memory by sending it.
+    pub max_len: usize,
+    /// What to do with a netstring that does not fit into the buffer given to
+    /// [read_netstring_with](crate::AsyncNetstringRead::read_netstring_with).
+    ///
+    /// Defaults to [OversizePolicy::Error].
+    pub oversize: OversizePolicy,
+    /// How strictly the length header is checked against the netstring specification.
+    ///
+    /// Defaults to [Strictness::Lenient].
+    pub strictness: Strictness,
+}
+
+impl Default for NetstringReadOptions {
+    fn default() -> Self {
+        NetstringReadOptions {
+            max_len: usize::MAX,
+            oversize: OversizePolicy::default(),
+            strictness: Strictness::default(),
+        }
+    }
+}
+
+/// The ways to handle a netstring, that is longer than the buffer it should be read into.
+///
+/// All errors returned wrap a [BufferTooSmall](crate::BufferTooSmall) holding the length
+/// announced by the header.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OversizePolicy {
+    /// Return an error of kind `ErrorKind::InvalidInput` right after the header has been read.
+    /// The payload can still be read with
+    /// [resume_netstring](crate::AsyncNetstringRead::resume_netstring).
+    #[default]
+    Error,
+    /// Discard the payload and the terminator, then return an error of kind
+    /// `ErrorKind::InvalidInput`. The stream can be used to read the next netstring.
+    Skip,
+    /// Fill the buffer with the beginning of the payload and discard the rest. The length
+    /// announced by the header is returned, which is larger than the buffer, so the caller can
+    /// tell that the netstring has been truncated.
+    Truncate,
+    /// Return an error of kind `ErrorKind::InvalidData`, like for an invalid netstring. The
+    /// stream is left in the middle of the netstring and the connection should be closed.
+    Fail,
+}
+
+/// How strictly the length header of a netstring is checked.
+///
+/// Both modes reject empty length headers, headers with any byte other than ascii digits
+/// before the `b':'`, lengths that don't fit into a `usize` and headers with more than 20
+/// digits, counting leading zeros, with
+/// [NetstringError::LengthOverflow](crate::NetstringError::LengthOverflow).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    /// Accept leading zeros, e.g. `007:abcdefg,`, as long as the header stays within 20 digits.
+    #[default]
+    Lenient,
+    /// Follow the netstring specification, which forbids leading zeros except for the empty
+    /// netstring `0:,`. Rejected headers fail with
+    /// [NetstringError::LeadingZero](crate::NetstringError::LeadingZero).
+    Strict,
+}
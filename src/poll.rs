@@ -0,0 +1,91 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf, Result};
+
+use crate::decoder::{DecodeEvent, NetstringDecoder};
+use crate::frame::{eof, ReadEvent};
+
+/// A function polling the next [ReadEvent] from a reader, which allows the futures to be
+/// driven by either [poll_read_event] or [poll_buf_read_event].
+pub(crate) type PollEvent<A> =
+    fn(&mut A, &mut NetstringDecoder, &mut [u8], &mut Context<'_>) -> Poll<Result<ReadEvent>>;
+
+/// Reads from `reader` until `decoder` emits the next event. The header and the terminator are
+/// read one byte at a time, while the payload is read into `body`, which must not be empty
+/// while the decoder expects payload. `ReadEvent::Body` holds the number of bytes written to
+/// the start of `body`.
+pub(crate) fn poll_read_event<A>(
+    reader: &mut A,
+    decoder: &mut NetstringDecoder,
+    body: &mut [u8],
+    cx: &mut Context<'_>,
+) -> Poll<Result<ReadEvent>>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    loop {
+        let remaining = decoder.body_remaining();
+        if remaining > 0 {
+            let len = remaining.min(body.len());
+            let read = {
+                let mut read_buf = ReadBuf::new(&mut body[..len]);
+                ready_and_ok!(Pin::new(&mut *reader).poll_read(cx, &mut read_buf));
+                bytes_read!(read_buf, decoder)
+            };
+            decoder.decode(&body[..read]);
+            return Poll::Ready(Ok(ReadEvent::Body(read)));
+        }
+
+        let byte = read_byte!(reader, decoder, cx);
+        match decoder.decode(&[byte]).1 {
+            None => {}
+            Some(DecodeEvent::Length(len)) => return Poll::Ready(Ok(ReadEvent::Length(len))),
+            Some(DecodeEvent::Body(_)) => unreachable!("the payload is read into the buffer"),
+            Some(DecodeEvent::Complete) => return Poll::Ready(Ok(ReadEvent::Complete)),
+            Some(DecodeEvent::Error(err)) => return Poll::Ready(Err(err.into())),
+        }
+    }
+}
+
+/// The buffered counterpart of [poll_read_event]. The header and the terminator are scanned
+/// straight out of the reader's buffer and the payload is copied from it, so no read is issued
+/// for data that is already buffered.
+pub(crate) fn poll_buf_read_event<A>(
+    reader: &mut A,
+    decoder: &mut NetstringDecoder,
+    body: &mut [u8],
+    cx: &mut Context<'_>,
+) -> Poll<Result<ReadEvent>>
+where
+    A: AsyncBufRead + Unpin + ?Sized,
+{
+    loop {
+        let input = ready_and_ok!(Pin::new(&mut *reader).poll_fill_buf(cx));
+        if input.is_empty() {
+            return Poll::Ready(Err(eof(decoder)));
+        }
+
+        //never decode more payload than fits into `body`
+        let input = match decoder.body_remaining() {
+            0 => input,
+            _ => &input[..input.len().min(body.len())],
+        };
+
+        let (consumed, event) = match decoder.decode(input) {
+            (consumed, None) => (consumed, None),
+            (consumed, Some(DecodeEvent::Length(len))) => (consumed, Some(ReadEvent::Length(len))),
+            (consumed, Some(DecodeEvent::Body(chunk))) => {
+                body[..chunk.len()].copy_from_slice(chunk);
+                (consumed, Some(ReadEvent::Body(chunk.len())))
+            }
+            (consumed, Some(DecodeEvent::Complete)) => (consumed, Some(ReadEvent::Complete)),
+            (_, Some(DecodeEvent::Error(err))) => return Poll::Ready(Err(err.into())),
+        };
+        Pin::new(&mut *reader).consume(consumed);
+
+        if let Some(event) = event {
+            return Poll::Ready(Ok(event));
+        }
+    }
+}
@@ -4,30 +4,58 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use pin_project_lite::pin_project;
-use tokio::io::{AsyncRead, Error, ErrorKind, ReadBuf, Result};
+use tokio::io::{AsyncRead, ReadBuf, Result};
 
-// usize::MAX.to_string().len() + one byte separator
-const MAX_LENGTH: usize = 21;
+use crate::decoder::NetstringDecoder;
+use crate::frame::{self, ReadEvent};
+use crate::instrument::{Direction, FrameTrace};
+use crate::logging;
+use crate::options::{NetstringReadOptions, OversizePolicy};
+use crate::poll::{poll_read_event, PollEvent};
 
 #[derive(Debug)]
 enum State {
-    Ready,
-    ReadLength([u8; MAX_LENGTH], usize),
-    ParseLength([u8; MAX_LENGTH], usize),
-    VerifyLength(usize, u8),
-    ParseSeparator(usize, u8),
-    ReadMessage(usize),
-    ParseTerminator,
+    ReadMessage,
+    DropMessage { len: usize },
 }
 
-pub(crate) fn read_netstring<'a, A>(reader: &'a mut A, buf: &'a mut [u8]) -> ReadMessage<'a, A>
+pub(crate) fn read_netstring<'a, A>(
+    reader: &'a mut A,
+    buf: &'a mut [u8],
+    options: NetstringReadOptions,
+    poll_event: PollEvent<A>,
+) -> ReadMessage<'a, A>
 where
     A: AsyncRead + Unpin + ?Sized,
 {
     ReadMessage {
         reader,
+        poll_event,
         buf: ReadBuf::new(buf),
-        state: State::Ready,
+        decoder: NetstringDecoder::with_options(options),
+        oversize: options.oversize,
+        state: State::ReadMessage,
+        trace: FrameTrace::start(Direction::Read),
+        _pin: PhantomPinned,
+    }
+}
+
+pub(crate) fn resume_netstring<'a, A>(
+    reader: &'a mut A,
+    buf: &'a mut [u8],
+    len: usize,
+) -> ReadMessage<'a, A>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    ReadMessage {
+        reader,
+        poll_event: poll_read_event,
+        buf: ReadBuf::new(buf),
+        decoder: NetstringDecoder::resume(len, NetstringReadOptions::default()),
+        oversize: OversizePolicy::Error,
+        state: State::ReadMessage,
+        trace: FrameTrace::start(Direction::Read),
         _pin: PhantomPinned,
     }
 }
@@ -40,14 +68,25 @@ pin_project! {
     #[must_use = "futures do nothing unless you `.await` or poll them"]
     pub struct ReadMessage<'a, A: ?Sized> {
         reader: &'a mut A,
+        poll_event: PollEvent<A>,
         buf: ReadBuf<'a>,
+        decoder: NetstringDecoder,
+        oversize: OversizePolicy,
         state: State,
+        trace: FrameTrace,
         // Make this future `!Unpin` for compatibility with async trait methods.
         #[pin]
         _pin: PhantomPinned,
     }
 }
 
+impl<A: ?Sized> ReadMessage<'_, A> {
+    /// Returns the decoder, which tells how far the netstring has been read.
+    pub(crate) fn decoder(&self) -> &NetstringDecoder {
+        &self.decoder
+    }
+}
+
 impl<A> Future for ReadMessage<'_, A>
 where
     A: AsyncRead + Unpin + ?Sized,
@@ -56,104 +95,84 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<usize>> {
         let me = self.project();
+        let res = {
+            let _entered = me.trace.enter();
+            ready!(poll_read_message(
+                &mut **me.reader,
+                *me.poll_event,
+                me.buf,
+                me.decoder,
+                *me.oversize,
+                me.state,
+                cx
+            ))
+        };
+
+        me.trace.finish_read(&res, |len| *len, me.decoder);
+        Poll::Ready(res)
+    }
+}
 
-        loop {
-            match me.state {
-                //initialize the state machine
-                State::Ready => {
-                    *me.state = State::ReadLength([0; MAX_LENGTH], 0);
-                }
-
-                //read the length of the netstring, one byte at a time
-                State::ReadLength(buf, prog) => {
-                    buf[*prog] = read_byte!(me.reader, cx);
-                    match *prog == MAX_LENGTH - 1 || !buf[*prog].is_ascii_digit() {
-                        true => *me.state = State::ParseLength(*buf, *prog),
-                        false => *prog += 1,
+fn poll_read_message<A>(
+    reader: &mut A,
+    poll_event: PollEvent<A>,
+    buf: &mut ReadBuf<'_>,
+    decoder: &mut NetstringDecoder,
+    oversize: OversizePolicy,
+    state: &mut State,
+    cx: &mut Context<'_>,
+) -> Poll<Result<usize>>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    loop {
+        match state {
+            //read the message into the buffer
+            State::ReadMessage => {
+                //verify that the message fits into the buffer
+                let remaining = decoder.body_remaining();
+                if remaining > buf.remaining() {
+                    let len = buf.filled().len() + remaining;
+                    match oversize {
+                        OversizePolicy::Error => {
+                            return Poll::Ready(Err(frame::buffer_to_small(len)))
+                        }
+                        OversizePolicy::Fail => {
+                            return Poll::Ready(Err(frame::buffer_to_small_fatal(len)))
+                        }
+                        OversizePolicy::Truncate if buf.remaining() > 0 => {}
+                        _ => {
+                            logging::log_netstring_len(logging::DROPPING, len);
+                            *state = State::DropMessage { len };
+                            continue;
+                        }
                     }
                 }
 
-                //parse the length, the last byte in the buffer is the first non-ascii digit.
-                State::ParseLength(buf, len) => {
-                    match String::from_utf8_lossy(&buf[..*len]).parse() {
-                        Ok(msg_len) => *me.state = State::VerifyLength(msg_len, buf[*len]),
-                        Err(_) => return integer_overflow(),
+                let body = buf.initialize_unfilled();
+                match ready_and_ok!(poll_event(reader, decoder, body, cx)) {
+                    ReadEvent::Length(_) => {}
+                    ReadEvent::Body(read) => buf.advance(read),
+                    ReadEvent::Complete => {
+                        let msg = buf.filled();
+                        logging::log_netstring(logging::READING, msg);
+                        return Poll::Ready(Ok(msg.len()));
                     }
                 }
+            }
 
-                //verify that the message fits into the buffer
-                State::VerifyLength(msg_len, separator) => match me.buf.remaining() {
-                    buf_size if buf_size < *msg_len => return buffer_to_small(),
-                    _ => *me.state = State::ParseSeparator(*msg_len, *separator),
-                },
-
-                //verify that length and message are separated by a ':'
-                State::ParseSeparator(len, separator) => match *separator {
-                    b':' => *me.state = State::ReadMessage(*len),
-                    sep => return wrong_separator(sep),
-                },
-
-                //read the message from the stream
-                State::ReadMessage(remaining) => match *remaining {
-                    0 => *me.state = State::ParseTerminator,
-                    _ => {
-                        let read = {
-                            let mut reader = (*me.buf).take(*remaining);
-                            ready_and_ok!(Pin::new(&mut *me.reader).poll_read(cx, &mut reader));
-                            bytes_read!(reader)
-                        };
-
-                        me.buf.advance(read);
-                        *remaining -= read;
-                    }
-                },
-
-                //verify that the message is terminated with a ','
-                State::ParseTerminator => {
-                    return match read_byte!(me.reader, cx) {
-                        b',' => Poll::Ready(Ok(me.buf.filled().len())),
-                        term => wrong_terminator(term),
-                    }
+            //discard the rest of a message that doesn't fit into the buffer
+            State::DropMessage { len } => {
+                let mut buf = [0; 1024];
+                if let ReadEvent::Complete =
+                    ready_and_ok!(poll_event(reader, decoder, &mut buf, cx))
+                {
+                    return match oversize {
+                        OversizePolicy::Truncate => Poll::Ready(Ok(*len)),
+                        _ => Poll::Ready(Err(frame::buffer_to_small(*len))),
+                    };
                 }
             }
         }
     }
 }
-
-fn eof() -> Error {
-    Error::new(ErrorKind::UnexpectedEof, "early eof")
-}
-
-fn integer_overflow() -> Poll<Result<usize>> {
-    Poll::Ready(Err(Error::new(
-        ErrorKind::InvalidData,
-        "ERROR: Integer overflow while parsing message length.".to_string(),
-    )))
-}
-
-fn buffer_to_small() -> Poll<Result<usize>> {
-    Poll::Ready(Err(Error::new(
-        ErrorKind::BrokenPipe,
-        "ERROR: Output buffer to small for message".to_string(),
-    )))
-}
-
-fn wrong_separator(separator: u8) -> Poll<Result<usize>> {
-    Poll::Ready(Err(Error::new(
-        ErrorKind::InvalidData,
-        format!(
-            "ERROR: Expected separator ':' but found {} instead",
-            separator as char
-        ),
-    )))
-}
-
-fn wrong_terminator(terminator: u8) -> Poll<Result<usize>> {
-    Poll::Ready(Err(Error::new(
-        ErrorKind::InvalidData,
-        format!(
-            "ERROR: Expected terminator ',' but found {} instead",
-            terminator as char
-        ),
-    )))
-}
@@ -4,29 +4,35 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use pin_project_lite::pin_project;
-use tokio::io::{AsyncRead, Error, ErrorKind, ReadBuf, Result};
+use tokio::io::{AsyncRead, Result};
 
-// usize::MAX.to_string().len() + one byte separator
-const MAX_LENGTH: usize = 21;
+use crate::decoder::NetstringDecoder;
+use crate::frame::{self, ReadEvent};
+use crate::instrument::{Direction, FrameTrace};
+use crate::logging;
+use crate::options::NetstringReadOptions;
+use crate::poll::PollEvent;
 
 #[derive(Debug)]
-enum State {
-    Ready,
-    ReadLength([u8; MAX_LENGTH], usize),
-    ParseLength([u8; MAX_LENGTH], usize),
-    ParseSeparator(usize, u8),
-    AllocateMemory(usize),
-    ReadMessage(Vec<u8>, usize),
-    ParseTerminator(Vec<u8>),
+pub(crate) struct State {
+    decoder: NetstringDecoder,
+    buf: Vec<u8>,
+    prog: usize,
+    trace: Option<FrameTrace>,
 }
 
-pub(crate) fn read_netstring_alloc<A>(reader: &mut A) -> ReadMessageAlloc<'_, A>
+pub(crate) fn read_netstring_alloc<A>(
+    reader: &mut A,
+    options: NetstringReadOptions,
+    poll_event: PollEvent<A>,
+) -> ReadMessageAlloc<'_, A>
 where
     A: AsyncRead + Unpin + ?Sized,
 {
     ReadMessageAlloc {
         reader,
-        state: State::Ready,
+        poll_event,
+        state: State::new(options),
         _pin: PhantomPinned,
     }
 }
@@ -35,10 +41,11 @@ pin_project! {
     /// Creates a future which will read exactly one message in the netstring format
     /// returning an error if EOF is hit sooner.
     ///
-    /// On success the number of bytes is returned
+    /// On success the message is returned as a `Vec<u8>`
     #[must_use = "futures do nothing unless you `.await` or poll them"]
     pub struct ReadMessageAlloc<'a, A: ?Sized> {
         reader: &'a mut A,
+        poll_event: PollEvent<A>,
         state: State,
         // Make this future `!Unpin` for compatibility with async trait methods.
         #[pin]
@@ -46,6 +53,13 @@ pin_project! {
     }
 }
 
+impl<A: ?Sized> ReadMessageAlloc<'_, A> {
+    /// Returns the decoder, which tells how far the netstring has been read.
+    pub(crate) fn decoder(&self) -> &NetstringDecoder {
+        self.state.decoder()
+    }
+}
+
 impl<A> Future for ReadMessageAlloc<'_, A>
 where
     A: AsyncRead + Unpin + ?Sized,
@@ -54,92 +68,159 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Vec<u8>>> {
         let me = self.project();
+        poll_read_netstring_alloc(&mut **me.reader, me.state, *me.poll_event, cx)
+    }
+}
 
-        loop {
-            match me.state {
-                //initialize the state machine
-                State::Ready => {
-                    *me.state = State::ReadLength([0; MAX_LENGTH], 0);
-                }
-
-                //read the length of the netstring, one byte at a time
-                State::ReadLength(buf, prog) => {
-                    buf[*prog] = read_byte!(me.reader, cx);
-                    match *prog == MAX_LENGTH - 1 || !buf[*prog].is_ascii_digit() {
-                        true => *me.state = State::ParseLength(*buf, *prog),
-                        false => *prog += 1,
-                    }
-                }
-
-                //parse the length, the last byte in the buffer is the first non-ascii digit.
-                State::ParseLength(buf, len) => {
-                    match String::from_utf8_lossy(&buf[..*len]).parse() {
-                        Ok(msg_len) => *me.state = State::ParseSeparator(msg_len, buf[*len]),
-                        Err(_) => return integer_overflow(),
-                    }
-                }
-
-                //verify that length and message are separated by a ':'
-                State::ParseSeparator(len, separator) => match *separator {
-                    b':' => *me.state = State::AllocateMemory(*len),
-                    sep => return wrong_separator(sep),
-                },
-
-                State::AllocateMemory(size) => *me.state = State::ReadMessage(vec![0; *size], 0),
-
-                //read the message from the stream
-                State::ReadMessage(buf, prog) => {
-                    if *prog == (buf.capacity()) {
-                        *me.state = State::ParseTerminator(std::mem::take(buf));
-                    } else {
-                        let read = {
-                            let mut reader = ReadBuf::new(&mut buf[*prog..]);
-                            ready_and_ok!(Pin::new(&mut *me.reader).poll_read(cx, &mut reader));
-                            bytes_read!(reader)
-                        };
-                        *prog += read;
-                    }
-                }
-
-                //verify that the message is terminated with a ','
-                State::ParseTerminator(buf) => {
-                    return match read_byte!(me.reader, cx) {
-                        b',' => return Poll::Ready(Ok(std::mem::take(buf))),
-                        term => wrong_terminator(term),
-                    }
-                }
-            }
-        }
+pub(crate) fn read_netstring_list<A>(inner: ReadMessageAlloc<'_, A>) -> ReadMessageList<'_, A>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    ReadMessageList { inner }
+}
+
+pin_project! {
+    /// Creates a future which will read exactly one message in the netstring format like
+    /// [ReadMessageAlloc] and decode the netstrings nested in its payload.
+    ///
+    /// On success the nested payloads are returned as a `Vec<Vec<u8>>`
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct ReadMessageList<'a, A: ?Sized> {
+        #[pin]
+        inner: ReadMessageAlloc<'a, A>,
+    }
+}
+
+impl<A> Future for ReadMessageList<'_, A>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    type Output = Result<Vec<Vec<u8>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Vec<Vec<u8>>>> {
+        let payload = ready_and_ok!(self.project().inner.poll(cx));
+        Poll::Ready(frame::decode_list(&payload))
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn read_netstring_serde<A, T>(
+    inner: ReadMessageAlloc<'_, A>,
+) -> ReadMessageSerde<'_, A, T>
+where
+    A: AsyncRead + Unpin + ?Sized,
+    T: serde::de::DeserializeOwned,
+{
+    ReadMessageSerde {
+        inner,
+        _value: std::marker::PhantomData,
     }
 }
 
-fn eof() -> Error {
-    Error::new(ErrorKind::UnexpectedEof, "early eof")
+#[cfg(feature = "serde")]
+pin_project! {
+    /// Creates a future which will read exactly one message in the netstring format like
+    /// [ReadMessageAlloc] and deserialize a value from it like
+    /// [from_netstring](crate::from_netstring).
+    ///
+    /// On success the value is returned
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct ReadMessageSerde<'a, A: ?Sized, T> {
+        #[pin]
+        inner: ReadMessageAlloc<'a, A>,
+        _value: std::marker::PhantomData<fn() -> T>,
+    }
 }
 
-fn integer_overflow() -> Poll<Result<Vec<u8>>> {
-    Poll::Ready(Err(Error::new(
-        ErrorKind::InvalidData,
-        "ERROR: Integer overflow while parsing message length.".to_string(),
-    )))
+#[cfg(feature = "serde")]
+impl<A, T> Future for ReadMessageSerde<'_, A, T>
+where
+    A: AsyncRead + Unpin + ?Sized,
+    T: serde::de::DeserializeOwned,
+{
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T>> {
+        let payload = ready_and_ok!(self.project().inner.poll(cx));
+        Poll::Ready(crate::de::from_payload(&payload).map_err(Into::into))
+    }
 }
 
-fn wrong_separator(separator: u8) -> Poll<Result<Vec<u8>>> {
-    Poll::Ready(Err(Error::new(
-        ErrorKind::InvalidData,
-        format!(
-            "ERROR: Expected separator ':' but found {} instead",
-            separator as char
-        ),
-    )))
+impl State {
+    pub(crate) fn new(options: NetstringReadOptions) -> Self {
+        State {
+            decoder: NetstringDecoder::with_options(options),
+            buf: Vec::new(),
+            prog: 0,
+            trace: None,
+        }
+    }
+
+    /// Returns the decoder, which tells how far the current netstring has been read.
+    pub(crate) fn decoder(&self) -> &NetstringDecoder {
+        &self.decoder
+    }
 }
 
-fn wrong_terminator(terminator: u8) -> Poll<Result<Vec<u8>>> {
-    Poll::Ready(Err(Error::new(
-        ErrorKind::InvalidData,
-        format!(
-            "ERROR: Expected terminator ',' but found {} instead",
-            terminator as char
-        ),
-    )))
+pub(crate) fn poll_read_netstring_alloc<A>(
+    reader: &mut A,
+    state: &mut State,
+    poll_event: PollEvent<A>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<Vec<u8>>>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    //the trace starts with the first poll, so a stream traces each netstring separately
+    let trace = state
+        .trace
+        .get_or_insert_with(|| FrameTrace::start(Direction::Read));
+    let res = {
+        let _entered = trace.enter();
+        ready!(poll_read_message_alloc(
+            reader,
+            &mut state.decoder,
+            &mut state.buf,
+            &mut state.prog,
+            poll_event,
+            cx
+        ))
+    };
+
+    if let Some(trace) = state.trace.take() {
+        trace.finish_read(&res, Vec::len, &state.decoder);
+    }
+    Poll::Ready(res)
+}
+
+fn poll_read_message_alloc<A>(
+    reader: &mut A,
+    decoder: &mut NetstringDecoder,
+    buf: &mut Vec<u8>,
+    prog: &mut usize,
+    poll_event: PollEvent<A>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<Vec<u8>>>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    loop {
+        if *prog == buf.len() && decoder.body_remaining() > 0 {
+            frame::grow_payload(buf, decoder.body_remaining())?;
+        }
+
+        let body = &mut buf[*prog..];
+        match ready_and_ok!(poll_event(reader, decoder, body, cx)) {
+            ReadEvent::Length(_) => {
+                buf.clear();
+                *prog = 0;
+            }
+            ReadEvent::Body(read) => *prog += read,
+            ReadEvent::Complete => {
+                *prog = 0;
+                logging::log_netstring(logging::READING, buf);
+                return Poll::Ready(Ok(std::mem::take(buf)));
+            }
+        }
+    }
 }
@@ -0,0 +1,232 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, ReadBuf, Result};
+
+use crate::decoder::{DecodeEvent, NetstringDecoder};
+use crate::frame::{self, COPY_BUFFER_SIZE};
+use crate::instrument::{Direction, FrameTrace};
+use crate::logging;
+use crate::options::NetstringReadOptions;
+
+/// Reads netstrings from an `AsyncRead`, that it owns together with a read buffer and the state
+/// of the netstring being read.
+///
+/// Unlike the futures returned by [AsyncNetstringRead](crate::AsyncNetstringRead), which lose
+/// the bytes read so far when they are dropped, [NetstringReader::next_frame] is cancel safe and
+/// can be used as a branch of `tokio::select!`.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::NetstringReader;
+///
+/// let mut reader = NetstringReader::new(stream);
+/// loop {
+///     tokio::select! {
+///         frame = reader.next_frame() => match frame? {
+///             Some(payload) => handle(payload),
+///             None => break,
+///         },
+///         _ = shutdown.recv() => break,
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct NetstringReader<R> {
+    reader: R,
+    state: State,
+    trace: Option<FrameTrace>,
+    done: bool,
+}
+
+/// The progress of reading one netstring, holding the bytes read but not yet decoded in
+/// `buf[pos..cap]`.
+#[derive(Debug)]
+struct State {
+    decoder: NetstringDecoder,
+    buf: Box<[u8]>,
+    pos: usize,
+    cap: usize,
+    payload: Vec<u8>,
+}
+
+impl State {
+    fn poll_frame<R>(&mut self, reader: &mut R, cx: &mut Context<'_>) -> Poll<Result<Vec<u8>>>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        loop {
+            //decode what has been read, before reading more
+            while self.pos < self.cap {
+                let (consumed, event) = self.decoder.decode(&self.buf[self.pos..self.cap]);
+                self.pos += consumed;
+
+                match event {
+                    None => {}
+                    //the payload grows with the bytes received, not the length announced
+                    Some(DecodeEvent::Length(len)) => {
+                        self.payload = Vec::with_capacity(len.min(COPY_BUFFER_SIZE))
+                    }
+                    Some(DecodeEvent::Body(chunk)) => self.payload.extend_from_slice(chunk),
+                    Some(DecodeEvent::Complete) => {
+                        logging::log_netstring(logging::READING, &self.payload);
+                        return Poll::Ready(Ok(std::mem::take(&mut self.payload)));
+                    }
+                    Some(DecodeEvent::Error(err)) => return Poll::Ready(Err(err.into())),
+                }
+            }
+
+            let mut read_buf = ReadBuf::new(&mut self.buf);
+            ready_and_ok!(Pin::new(&mut *reader).poll_read(cx, &mut read_buf));
+            match read_buf.filled().len() {
+                0 => return Poll::Ready(Err(frame::eof(&self.decoder))),
+                read => {
+                    self.pos = 0;
+                    self.cap = read;
+                }
+            }
+        }
+    }
+}
+
+impl<R> NetstringReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Creates a new `NetstringReader` without an upper limit on the length of a netstring.
+    ///
+    /// The payload is collected as it arrives and kept across cancelled calls, so without a
+    /// limit a peer can make the reader hold as much memory as it is willing to send before the
+    /// terminator. Outside of tests, use [NetstringReader::with_options] with a `max_len` that
+    /// fits the protocol instead.
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, NetstringReadOptions::default())
+    }
+
+    /// Creates a new `NetstringReader` that applies the limits and the strictness set in
+    /// `options`. [NetstringReadOptions::oversize] is ignored, as every payload is read into
+    /// its own `Vec<u8>`.
+    pub fn with_options(reader: R, options: NetstringReadOptions) -> Self {
+        NetstringReader {
+            reader,
+            state: State {
+                decoder: NetstringDecoder::with_options(options),
+                buf: vec![0; COPY_BUFFER_SIZE].into_boxed_slice(),
+                pos: 0,
+                cap: 0,
+                payload: Vec::new(),
+            },
+            trace: None,
+            done: false,
+        }
+    }
+
+    /// Reads the next netstring and returns its payload, or `None` if the reader has ended.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe. Should the returned future be dropped before it completes,
+    /// e.g. because another branch of `tokio::select!` completed first, the bytes read so far
+    /// stay in the `NetstringReader` and the next call continues with them, so no netstring is
+    /// lost or read twice.
+    ///
+    /// # Errors
+    /// The reader ends when the underlying reader reaches EOF between two netstrings. Should it
+    /// reach EOF in the middle of a netstring or the framing be invalid, the error is returned
+    /// once, like by
+    /// [AsyncNetstringRead::read_netstring_alloc](crate::AsyncNetstringRead::read_netstring_alloc),
+    /// and the reader ends afterwards, as the position in the underlying stream is lost.
+    pub fn next_frame(&mut self) -> NextFrame<'_, R> {
+        NextFrame { reader: self }
+    }
+
+    /// Polls to read the next netstring, returning its payload, or `None` if the reader has
+    /// ended. Like [NetstringReader::next_frame], it keeps its progress across calls.
+    pub fn poll_next_frame(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Vec<u8>>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        //the trace starts with the first poll, so every netstring is traced separately
+        let trace = self
+            .trace
+            .get_or_insert_with(|| FrameTrace::start(Direction::Read));
+        let res = {
+            let _entered = trace.enter();
+            ready!(self.state.poll_frame(&mut self.reader, cx))
+        };
+
+        let trace = self.trace.take();
+        if let Err(err) = &res {
+            self.done = true;
+            if frame::is_eof_at_boundary(err, &self.state.decoder) {
+                return Poll::Ready(None);
+            }
+        }
+
+        if let Some(trace) = trace {
+            trace.finish_read(&res, Vec::len, &self.state.decoder);
+        }
+        Poll::Ready(Some(res))
+    }
+}
+
+impl<R> NetstringReader<R> {
+    /// Returns the bytes that have been read from the underlying reader, but not decoded yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.state.buf[self.state.pos..self.state.cap]
+    }
+
+    /// Returns a shared reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader. Reading from it directly
+    /// desynchronizes the `NetstringReader`, unless it is between two netstrings and
+    /// [NetstringReader::buffer] is empty.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the `NetstringReader`, returning the underlying reader. The bytes returned by
+    /// [NetstringReader::buffer] are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<R> futures_core::Stream for NetstringReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Vec<u8>>>> {
+        self.get_mut().poll_next_frame(cx)
+    }
+}
+
+/// Creates a future which will read the next netstring of a [NetstringReader], created by
+/// [NetstringReader::next_frame].
+///
+/// On success the payload is returned, or `None` if the reader has ended. The future holds no
+/// state of its own, so dropping it loses nothing.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct NextFrame<'a, R> {
+    reader: &'a mut NetstringReader<R>,
+}
+
+impl<R> Future for NextFrame<'_, R>
+where
+    R: AsyncRead + Unpin,
+{
+    type Output = Result<Option<Vec<u8>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Option<Vec<u8>>>> {
+        let me = self.get_mut();
+        Poll::Ready(ready!(me.reader.poll_next_frame(cx)).transpose())
+    }
+}
@@ -0,0 +1,421 @@
+use std::fmt::Display;
+
+use serde::ser::{self, Serialize};
+
+use crate::encoder::{encode_header, encode_to_vec, MAX_HEADER_LENGTH};
+use crate::error::SerdeError;
+
+/// Serializes `value` into one netstring.
+///
+/// Scalars are written as text, i.e. numbers in decimal, booleans as `true` or `false` and
+/// strings as UTF-8, while bytes are written unchanged. Everything else nests netstrings:
+///
+/// - sequences, tuples and tuple structs as a list of their items,
+/// - maps and structs as a list alternating between keys, or field names, and values,
+/// - enum variants as a list of the variant name followed by its content, if any,
+/// - `None` and `Some(value)` as a list of zero or one item.
+///
+/// Newtype structs are written as their content and unit values as an empty netstring.
+///
+/// # Usage
+/// ```no_exec
+/// use tokio_netstring_trait::to_netstring;
+///
+/// let netstring = to_netstring(&("Hello", 13, vec![true]))?;
+/// assert_eq!(b"23:5:Hello,2:13,7:4:true,,,", &netstring[..]);
+/// ```
+///
+/// # Errors
+/// It returns [SerdeError::Message] if the `Serialize` implementation of the value fails.
+pub fn to_netstring<T>(value: &T) -> Result<Vec<u8>, SerdeError>
+where
+    T: Serialize + ?Sized,
+{
+    let mut serializer = Serializer { output: Vec::new() };
+    value.serialize(&mut serializer)?;
+    Ok(serializer.output)
+}
+
+struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    fn scalar(&mut self, text: &[u8]) {
+        encode_to_vec(text, &mut self.output);
+    }
+
+    fn display<T: Display>(&mut self, value: T) {
+        self.scalar(value.to_string().as_bytes());
+    }
+
+    /// Starts a list, whose header is inserted once its length is known.
+    fn open(&mut self) -> usize {
+        self.output.len()
+    }
+
+    fn close(&mut self, start: usize) {
+        let mut header = [0; MAX_HEADER_LENGTH];
+        let header = encode_header(self.output.len() - start, &mut header);
+        self.output.splice(start..start, header.iter().copied());
+        self.output.push(b',');
+    }
+
+    fn compound(&mut self, outer: Option<usize>) -> Compound<'_> {
+        Compound {
+            start: self.open(),
+            outer,
+            ser: self,
+        }
+    }
+
+    fn variant(&mut self, variant: &str) -> usize {
+        let outer = self.open();
+        self.scalar(variant.as_bytes());
+        outer
+    }
+}
+
+/// A list, that is written by one of the `Serialize*` traits. Variants of an enum carry their
+/// content in a second list nested in the one holding the variant name.
+struct Compound<'a> {
+    ser: &'a mut Serializer,
+    start: usize,
+    outer: Option<usize>,
+}
+
+impl Compound<'_> {
+    fn item<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn close(self) -> Result<(), SerdeError> {
+        self.ser.close(self.start);
+        if let Some(outer) = self.outer {
+            self.ser.close(outer);
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = SerdeError;
+
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), SerdeError> {
+        self.display(v);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), SerdeError> {
+        self.scalar(v.encode_utf8(&mut [0; 4]).as_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), SerdeError> {
+        self.scalar(v.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), SerdeError> {
+        self.scalar(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), SerdeError> {
+        self.scalar(b"");
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let start = self.open();
+        value.serialize(&mut *self)?;
+        self.close(start);
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), SerdeError> {
+        self.scalar(b"");
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerdeError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerdeError> {
+        let outer = self.variant(variant);
+        self.close(outer);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let outer = self.variant(variant);
+        value.serialize(&mut *self)?;
+        self.close(outer);
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>, SerdeError> {
+        Ok(self.compound(None))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a>, SerdeError> {
+        Ok(self.compound(None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, SerdeError> {
+        Ok(self.compound(None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, SerdeError> {
+        let outer = self.variant(variant);
+        Ok(self.compound(Some(outer)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>, SerdeError> {
+        Ok(self.compound(None))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, SerdeError> {
+        Ok(self.compound(None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, SerdeError> {
+        let outer = self.variant(variant);
+        Ok(self.compound(Some(outer)))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(key)?;
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeError>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.item(key)?;
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.close()
+    }
+}
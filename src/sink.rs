@@ -0,0 +1,123 @@
+use std::io::{ErrorKind, Result};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_sink::Sink;
+use tokio::io::AsyncWrite;
+
+use crate::encoder;
+use crate::instrument::{self, Direction};
+use crate::logging;
+
+// Once this many bytes are buffered, `poll_ready` writes them out before accepting more.
+const BACKPRESSURE_BOUNDARY: usize = 8 * 1024;
+
+/// A sink writing every item as one netstring to an `AsyncWrite`.
+///
+/// Items are buffered until the sink is flushed or the buffer grows beyond an internal
+/// boundary, so a batch of small messages is written with few calls to the writer.
+///
+/// # Usage
+/// ```no_exec
+/// use futures::SinkExt;
+/// use tokio_netstring_trait::NetstringSink;
+///
+/// let mut sink = NetstringSink::new(stream);
+/// sink.send("Hello, World!").await?;
+/// ```
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct NetstringSink<W> {
+    writer: W,
+    buf: Vec<u8>,
+}
+
+impl<W> NetstringSink<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    /// Creates a new `NetstringSink` writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        NetstringSink {
+            writer,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Returns a shared reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer. Writing to it directly while
+    /// netstrings are still buffered will interleave the streams.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consumes the sink, returning the underlying writer. Any netstrings that have not been
+    /// flushed yet are lost.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut prog = 0;
+        let res = loop {
+            if prog == self.buf.len() {
+                break Poll::Ready(Ok(()));
+            }
+
+            match Pin::new(&mut self.writer).poll_write(cx, &self.buf[prog..]) {
+                Poll::Ready(Ok(0)) => break Poll::Ready(Err(ErrorKind::WriteZero.into())),
+                Poll::Ready(Ok(wrote)) => prog += wrote,
+                res => break res.map_ok(|_| ()),
+            }
+        };
+
+        self.buf.drain(..prog);
+        res
+    }
+}
+
+impl<W, I> Sink<I> for NetstringSink<W>
+where
+    W: AsyncWrite + Unpin,
+    I: AsRef<[u8]>,
+{
+    type Error = std::io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.get_mut();
+        match me.buf.len() < BACKPRESSURE_BOUNDARY {
+            true => Poll::Ready(Ok(())),
+            false => me.poll_write_buf(cx),
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<()> {
+        let item = item.as_ref();
+        let buf = &mut self.get_mut().buf;
+        instrument::trace_frame(
+            Direction::Write,
+            |_| item.len(),
+            || {
+                logging::log_netstring(logging::WRITING, item);
+                encoder::encode_to_vec(item, buf);
+                Ok(())
+            },
+        )
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.get_mut();
+        ready_and_ok!(me.poll_write_buf(cx));
+        Pin::new(&mut me.writer).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.get_mut();
+        ready_and_ok!(me.poll_write_buf(cx));
+        Pin::new(&mut me.writer).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::Result;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::AsyncRead;
+
+use crate::frame;
+use crate::options::NetstringReadOptions;
+use crate::poll::poll_read_event;
+use crate::read_alloc::{self, State};
+
+pub(crate) fn netstrings<R>(reader: R, options: NetstringReadOptions) -> Netstrings<R>
+where
+    R: AsyncRead + Unpin,
+{
+    Netstrings {
+        reader,
+        state: State::new(options),
+        done: false,
+    }
+}
+
+/// A stream over the netstrings read from an `AsyncRead`, created by
+/// [AsyncNetstringRead::netstrings](crate::AsyncNetstringRead::netstrings).
+///
+/// The stream ends when the reader reaches EOF between two netstrings. Should the reader
+/// close in the middle of a netstring or the framing be invalid, the error is yielded once and
+/// the stream ends afterwards, as the position in the underlying stream is lost.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Netstrings<R> {
+    reader: R,
+    state: State,
+    done: bool,
+}
+
+impl<R> Netstrings<R> {
+    /// Returns a shared reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the stream, returning the underlying reader. Should a netstring be partially
+    /// read, its content is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Stream for Netstrings<R>
+where
+    R: AsyncRead + Unpin,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Vec<u8>>>> {
+        let me = self.get_mut();
+
+        if me.done {
+            return Poll::Ready(None);
+        }
+
+        match ready!(read_alloc::poll_read_netstring_alloc(
+            &mut me.reader,
+            &mut me.state,
+            poll_read_event,
+            cx
+        )) {
+            Ok(msg) => Poll::Ready(Some(Ok(msg))),
+            Err(err) => {
+                me.done = true;
+                match frame::is_eof_at_boundary(&err, me.state.decoder()) {
+                    true => Poll::Ready(None),
+                    false => Poll::Ready(Some(Err(err))),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::future::Future;
+use std::marker::Unpin;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, Result};
+
+use crate::frame;
+use crate::read::ReadMessage;
+use crate::read_alloc::ReadMessageAlloc;
+
+pub(crate) fn try_read_netstring<A>(inner: ReadMessage<'_, A>) -> TryReadMessage<'_, A>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    TryReadMessage { inner }
+}
+
+pub(crate) fn try_read_netstring_alloc<A>(
+    inner: ReadMessageAlloc<'_, A>,
+) -> TryReadMessageAlloc<'_, A>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    TryReadMessageAlloc { inner }
+}
+
+pin_project! {
+    /// Creates a future which will read exactly one message in the netstring format like
+    /// [ReadMessage], but returns `None` if EOF is hit before the message started.
+    ///
+    /// On success the number of bytes is returned
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TryReadMessage<'a, A: ?Sized> {
+        #[pin]
+        inner: ReadMessage<'a, A>,
+    }
+}
+
+impl<A> Future for TryReadMessage<'_, A>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    type Output = Result<Option<usize>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Option<usize>>> {
+        let mut me = self.project();
+        match ready!(me.inner.as_mut().poll(cx)) {
+            Ok(len) => Poll::Ready(Ok(Some(len))),
+            Err(err) if frame::is_eof_at_boundary(&err, me.inner.decoder()) => {
+                Poll::Ready(Ok(None))
+            }
+            Err(err) => Poll::Ready(Err(err)),
+        }
+    }
+}
+
+pin_project! {
+    /// Creates a future which will read exactly one message in the netstring format like
+    /// [ReadMessageAlloc], but returns `None` if EOF is hit before the message started.
+    ///
+    /// On success the message is returned as a `Vec<u8>`
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TryReadMessageAlloc<'a, A: ?Sized> {
+        #[pin]
+        inner: ReadMessageAlloc<'a, A>,
+    }
+}
+
+impl<A> Future for TryReadMessageAlloc<'_, A>
+where
+    A: AsyncRead + Unpin + ?Sized,
+{
+    type Output = Result<Option<Vec<u8>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Option<Vec<u8>>>> {
+        let mut me = self.project();
+        match ready!(me.inner.as_mut().poll(cx)) {
+            Ok(msg) => Poll::Ready(Ok(Some(msg))),
+            Err(err) if frame::is_eof_at_boundary(&err, me.inner.decoder()) => {
+                Poll::Ready(Ok(None))
+            }
+            Err(err) => Poll::Ready(Err(err)),
+        }
+    }
+}
@@ -1,41 +1,129 @@
 use std::future::Future;
+use std::iter::Fuse;
 use std::marker::{PhantomPinned, Unpin};
+use std::ops::Deref;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use pin_project_lite::pin_project;
 use tokio::io::{AsyncWrite, ErrorKind, Result};
 
-pub(crate) fn write_netstring<'a, A>(writer: &'a mut A, buf: &'a [u8]) -> WriteMessage<'a, A>
+use crate::frame::{self, Frame, COALESCE_LIMIT};
+use crate::instrument::{Direction, FrameTrace};
+use crate::logging;
+
+pub(crate) fn write_netstring<'a, A>(
+    writer: &'a mut A,
+    buf: &'a [u8],
+    flush: bool,
+) -> WriteMessage<'a, A>
 where
     A: AsyncWrite + Unpin + ?Sized,
 {
-    const NETSTRING_MAX_OVERHEAD: usize = 22; // usize::MAX.to_string().len() + b':'.len() + b','.len()
-
-    let mut buffer = Vec::with_capacity(buf.len() + NETSTRING_MAX_OVERHEAD);
-    buffer.extend_from_slice(buf.len().to_string().as_bytes());
-    buffer.extend_from_slice(&[b':']);
-    buffer.extend_from_slice(buf);
-    buffer.extend_from_slice(&[b',']);
+    let coalesced = coalesce(writer, &Frame::new(&[buf]));
 
     WriteMessage {
         writer,
-        buf: buffer,
+        data: buf,
+        coalesced,
+        prog: 0,
+        flush,
+        trace: FrameTrace::start(Direction::Write),
+        _pin: PhantomPinned,
+    }
+}
+
+pub(crate) fn write_netstring_parts<'a, A, T>(
+    writer: &'a mut A,
+    parts: &'a [T],
+) -> WriteMessageParts<'a, A, T>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+    T: Deref<Target = [u8]>,
+{
+    let coalesced = coalesce(writer, &Frame::new(parts));
+
+    WriteMessageParts {
+        writer,
+        parts,
+        coalesced,
+        prog: 0,
+        trace: FrameTrace::start(Direction::Write),
+        _pin: PhantomPinned,
+    }
+}
+
+pub(crate) fn write_netstring_list<'a, A>(
+    writer: &'a mut A,
+    items: &[&[u8]],
+) -> WriteMessageList<'a, A>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+{
+    let mut buf = Vec::new();
+    crate::list::encode_list_to_vec(items, &mut buf);
+
+    WriteMessageList {
+        writer,
+        buf,
+        prog: 0,
+        trace: FrameTrace::start(Direction::Write),
+        _pin: PhantomPinned,
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn write_netstring_serde<'a, A, T>(
+    writer: &'a mut A,
+    value: &T,
+) -> WriteMessageSerde<'a, A>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+    T: serde::Serialize + ?Sized,
+{
+    let buf = crate::ser::to_netstring(value);
+
+    WriteMessageSerde {
+        writer,
+        buf,
         prog: 0,
-        _pin: Default::default(),
+        trace: FrameTrace::start(Direction::Write),
+        _pin: PhantomPinned,
+    }
+}
+
+pub(crate) fn write_netstrings<A, I>(writer: &mut A, netstrings: I) -> WriteMessages<'_, A, I>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    WriteMessages {
+        writer,
+        netstrings: netstrings.fuse(),
+        current: None,
+        coalesced: None,
+        prog: 0,
+        trace: None,
+        _pin: PhantomPinned,
     }
 }
 
 pin_project! {
-    /// Creates a future which will read exactly enough bytes to fill `buf`,
-    /// returning an error if EOF is hit sooner.
+    /// Creates a future which will write exactly one message in the netstring format
+    /// and flush the writer afterwards, returning an error if the writer is closed sooner.
     ///
-    /// On success the number of bytes is returned
+    /// The header, the payload and the terminator are written with one vectored write, if the
+    /// writer supports it. Otherwise small payloads are copied into one buffer together with
+    /// the header and the terminator, while large ones are written without being copied.
     #[must_use = "futures do nothing unless you `.await` or poll them"]
     pub struct WriteMessage<'a, A: ?Sized> {
         writer: &'a mut A,
-        buf: Vec<u8>,
+        data: &'a [u8],
+        coalesced: Option<Vec<u8>>,
         prog: usize,
+        flush: bool,
+        trace: FrameTrace,
         // Make this future `!Unpin` for compatibility with async trait methods.
         #[pin]
         _pin: PhantomPinned,
@@ -46,20 +134,329 @@ impl<A> Future for WriteMessage<'_, A>
 where
     A: AsyncWrite + Unpin + ?Sized,
 {
-    type Output = Result<usize>;
+    type Output = Result<()>;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.project();
+
+        let frame = Frame::new(std::slice::from_ref(me.data));
+        let res = {
+            let _entered = me.trace.enter();
+            ready!(poll_write_frame_and_flush(
+                &mut **me.writer,
+                &frame,
+                me.coalesced.as_deref(),
+                me.prog,
+                *me.flush,
+                cx
+            ))
+        };
+
+        if res.is_ok() {
+            logging::log_netstring(logging::WRITING, me.data);
+        }
+        me.trace.finish(&res, |()| frame.data_len());
+        Poll::Ready(res)
+    }
+}
+
+pin_project! {
+    /// Creates a future which will write exactly one message in the netstring format, whose
+    /// payload consists of several parts, and flush the writer afterwards, returning an error if
+    /// the writer is closed sooner.
+    ///
+    /// The parts are written the same way as the payload of a [WriteMessage].
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WriteMessageParts<'a, A: ?Sized, T> {
+        writer: &'a mut A,
+        parts: &'a [T],
+        coalesced: Option<Vec<u8>>,
+        prog: usize,
+        trace: FrameTrace,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+impl<A, T> Future for WriteMessageParts<'_, A, T>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+    T: Deref<Target = [u8]>,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.project();
+
+        let frame = Frame::new(me.parts);
+        let res = {
+            let _entered = me.trace.enter();
+            ready!(poll_write_frame_and_flush(
+                &mut **me.writer,
+                &frame,
+                me.coalesced.as_deref(),
+                me.prog,
+                true,
+                cx
+            ))
+        };
+
+        if res.is_ok() {
+            logging::log_netstring_parts(logging::WRITING, me.parts);
+        }
+        me.trace.finish(&res, |()| frame.data_len());
+        Poll::Ready(res)
+    }
+}
+
+pin_project! {
+    /// Creates a future which will write exactly one message in the netstring format, whose
+    /// payload nests a list of netstrings, and flush the writer afterwards, returning an error if
+    /// the writer is closed sooner.
+    ///
+    /// The list is encoded into one buffer up front.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WriteMessageList<'a, A: ?Sized> {
+        writer: &'a mut A,
+        buf: Vec<u8>,
+        prog: usize,
+        trace: FrameTrace,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+impl<A> Future for WriteMessageList<'_, A>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.project();
+        let res = {
+            let _entered = me.trace.enter();
+            ready!(poll_write_buf(&mut **me.writer, me.buf, me.prog, cx))
+        };
+
+        if res.is_ok() {
+            logging::log_encoded(logging::WRITING, me.buf);
+        }
+        me.trace.finish(&res, |()| frame::payload_len(me.buf));
+        Poll::Ready(res)
+    }
+}
+
+#[cfg(feature = "serde")]
+pin_project! {
+    /// Creates a future which will serialize a value like [to_netstring](crate::to_netstring),
+    /// write it and flush the writer afterwards, returning an error if the writer is closed
+    /// sooner.
+    ///
+    /// The value is serialized into one buffer up front. Should that fail, the future returns
+    /// an error of kind `ErrorKind::InvalidData` without writing anything.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WriteMessageSerde<'a, A: ?Sized> {
+        writer: &'a mut A,
+        buf: std::result::Result<Vec<u8>, crate::error::SerdeError>,
+        prog: usize,
+        trace: FrameTrace,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<A> Future for WriteMessageSerde<'_, A>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.project();
+        let res = {
+            let _entered = me.trace.enter();
+            match me.buf {
+                Ok(buf) => ready!(poll_write_buf(&mut **me.writer, buf, me.prog, cx)),
+                Err(err) => Err(err.clone().into()),
+            }
+        };
+
+        if let (Ok(()), Ok(buf)) = (&res, &*me.buf) {
+            logging::log_encoded(logging::WRITING, buf);
+        }
+        me.trace
+            .finish(&res, |()| me.buf.as_deref().map_or(0, frame::payload_len));
+        Poll::Ready(res)
+    }
+}
+
+pin_project! {
+    /// Creates a future which will write every item of an iterator as one message in the
+    /// netstring format and flush the writer once all of them are written, returning an error
+    /// if the writer is closed sooner.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WriteMessages<'a, A: ?Sized, I: Iterator> {
+        writer: &'a mut A,
+        // Fused, so a pending flush can't make the iterator yield again after it ended.
+        netstrings: Fuse<I>,
+        current: Option<I::Item>,
+        coalesced: Option<Vec<u8>>,
+        prog: usize,
+        trace: Option<FrameTrace>,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+impl<A, I> Future for WriteMessages<'_, A, I>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
         let me = self.project();
 
         loop {
-            if *me.prog == me.buf.len() {
-                return Poll::Ready(Ok(*me.prog));
+            if me.current.is_none() {
+                match me.netstrings.next() {
+                    Some(data) => {
+                        let buf = data.as_ref();
+                        *me.coalesced = coalesce(&**me.writer, &Frame::new(&[buf]));
+                        *me.prog = 0;
+                        *me.current = Some(data);
+                        *me.trace = Some(FrameTrace::start(Direction::Write));
+                    }
+                    None => return Pin::new(&mut *me.writer).poll_flush(cx),
+                }
             }
 
-            match ready_and_ok!(Pin::new(&mut *me.writer).poll_write(cx, &me.buf[*me.prog..])) {
-                0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
-                wrote => *me.prog += wrote,
+            let data = match &*me.current {
+                Some(data) => data.as_ref(),
+                None => unreachable!("the next netstring has been taken above"),
+            };
+
+            let parts = [data];
+            let frame = Frame::new(&parts);
+            let res = {
+                let _entered = me.trace.as_ref().map(FrameTrace::enter);
+                ready!(poll_write_frame(
+                    &mut **me.writer,
+                    &frame,
+                    me.coalesced.as_deref(),
+                    me.prog,
+                    cx
+                ))
+            };
+
+            if res.is_ok() {
+                logging::log_netstring(logging::WRITING, data);
+            }
+            if let Some(trace) = me.trace.take() {
+                trace.finish(&res, |()| data.len());
             }
+            if let Err(err) = res {
+                return Poll::Ready(Err(err));
+            }
+            *me.current = None;
+        }
+    }
+}
+
+/// Writes `buf` after the first `prog` bytes and flushes the writer afterwards.
+fn poll_write_buf<A>(
+    writer: &mut A,
+    buf: &[u8],
+    prog: &mut usize,
+    cx: &mut Context<'_>,
+) -> Poll<Result<()>>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+{
+    while *prog < buf.len() {
+        match ready_and_ok!(Pin::new(&mut *writer).poll_write(cx, &buf[*prog..])) {
+            0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+            wrote => *prog += wrote,
+        }
+    }
+
+    Pin::new(&mut *writer).poll_flush(cx)
+}
+
+/// Writes the part of `frame` that follows the first `prog` bytes like [poll_write_frame] and
+/// flushes the writer afterwards, if `flush` is set.
+fn poll_write_frame_and_flush<A, T>(
+    writer: &mut A,
+    frame: &Frame<'_, T>,
+    coalesced: Option<&[u8]>,
+    prog: &mut usize,
+    flush: bool,
+    cx: &mut Context<'_>,
+) -> Poll<Result<()>>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+    T: Deref<Target = [u8]>,
+{
+    ready_and_ok!(poll_write_frame(writer, frame, coalesced, prog, cx));
+
+    match flush {
+        true => Pin::new(&mut *writer).poll_flush(cx),
+        false => Poll::Ready(Ok(())),
+    }
+}
+
+/// Copies small netstrings into one buffer, should the writer not support vectored writes, so
+/// they are written with one call.
+fn coalesce<A, T>(writer: &A, frame: &Frame<'_, T>) -> Option<Vec<u8>>
+where
+    A: AsyncWrite + ?Sized,
+    T: Deref<Target = [u8]>,
+{
+    match writer.is_write_vectored() || frame.data_len() > COALESCE_LIMIT {
+        true => None,
+        false => Some(frame.to_vec()),
+    }
+}
+
+/// Writes the part of `frame` that follows the first `prog` bytes, either from `coalesced`,
+/// holding the whole netstring, or with vectored writes, if the writer supports them.
+fn poll_write_frame<A, T>(
+    writer: &mut A,
+    frame: &Frame<'_, T>,
+    coalesced: Option<&[u8]>,
+    prog: &mut usize,
+    cx: &mut Context<'_>,
+) -> Poll<Result<()>>
+where
+    A: AsyncWrite + Unpin + ?Sized,
+    T: Deref<Target = [u8]>,
+{
+    loop {
+        if *prog == frame.encoded_len() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let (slices, len) = frame.remaining(*prog);
+        let writer = Pin::new(&mut *writer);
+        let wrote = match coalesced {
+            Some(buf) => ready_and_ok!(writer.poll_write(cx, &buf[*prog..])),
+            None if writer.is_write_vectored() => {
+                ready_and_ok!(writer.poll_write_vectored(cx, &slices[..len]))
+            }
+            None => ready_and_ok!(writer.poll_write(cx, &slices[0])),
+        };
+
+        match wrote {
+            0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+            wrote => *prog += wrote,
         }
     }
 }
@@ -0,0 +1,285 @@
+#[cfg(feature = "fs")]
+use std::fs::Metadata;
+use std::future::Future;
+use std::marker::{PhantomPinned, Unpin};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+#[cfg(feature = "fs")]
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncWrite, ErrorKind, ReadBuf, Result};
+
+use crate::encoder::{self, MAX_HEADER_LENGTH};
+use crate::frame::{self, COPY_BUFFER_SIZE};
+use crate::instrument::{Direction, FrameTrace};
+use crate::logging;
+
+#[derive(Debug)]
+enum State {
+    Header,
+    Body,
+    Trailer,
+    Terminator,
+    Flush,
+}
+
+pub(crate) fn write_netstring_from_reader<'a, W, R>(
+    writer: &'a mut W,
+    len: usize,
+    reader: &'a mut R,
+) -> WriteMessageFromReader<'a, W, R>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+    R: AsyncRead + Unpin + ?Sized,
+{
+    WriteMessageFromReader {
+        writer,
+        reader,
+        transfer: Transfer::new(len),
+        trace: FrameTrace::start(Direction::Write),
+        _pin: PhantomPinned,
+    }
+}
+
+pin_project! {
+    /// Creates a future which will write exactly one message in the netstring format, whose
+    /// payload is copied from a reader, and flush the writer afterwards.
+    ///
+    /// It returns an error of kind `ErrorKind::UnexpectedEof`, if the reader ends before the
+    /// announced number of bytes has been copied, and an error of kind `ErrorKind::InvalidInput`,
+    /// if the reader holds more bytes. In both cases the terminator is not written, so the peer
+    /// can't mistake the incomplete payload for a valid netstring, and the connection should be
+    /// closed.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WriteMessageFromReader<'a, W: ?Sized, R: ?Sized> {
+        writer: &'a mut W,
+        reader: &'a mut R,
+        transfer: Transfer,
+        trace: FrameTrace,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+impl<W, R> Future for WriteMessageFromReader<'_, W, R>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+    R: AsyncRead + Unpin + ?Sized,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.project();
+        let res = {
+            let _entered = me.trace.enter();
+            ready!(me.transfer.poll(&mut **me.writer, &mut **me.reader, cx))
+        };
+
+        if res.is_ok() {
+            logging::log_netstring_len(logging::WRITING, me.transfer.len);
+        }
+        me.trace.finish(&res, |()| me.transfer.len);
+        Poll::Ready(res)
+    }
+}
+
+/// The progress of copying the payload from the reader into the frame written.
+#[derive(Debug)]
+struct Transfer {
+    header: [u8; MAX_HEADER_LENGTH],
+    header_start: usize,
+    len: usize,
+    remaining: usize,
+    buf: Box<[u8]>,
+    pos: usize,
+    cap: usize,
+    state: State,
+}
+
+impl Transfer {
+    fn new(len: usize) -> Self {
+        let mut header = [0; MAX_HEADER_LENGTH];
+        let header_start = MAX_HEADER_LENGTH - encoder::encode_header(len, &mut header).len();
+
+        Transfer {
+            header,
+            header_start,
+            len,
+            remaining: len,
+            buf: vec![0; len.min(COPY_BUFFER_SIZE)].into_boxed_slice(),
+            pos: 0,
+            cap: 0,
+            state: State::Header,
+        }
+    }
+
+    fn poll<W, R>(
+        &mut self,
+        writer: &mut W,
+        reader: &mut R,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<()>>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        loop {
+            match self.state {
+                State::Header => {
+                    let header = &self.header[self.header_start..];
+                    match ready_and_ok!(Pin::new(&mut *writer).poll_write(cx, header)) {
+                        0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+                        wrote => self.header_start += wrote,
+                    }
+                    if self.header_start == MAX_HEADER_LENGTH {
+                        self.state = State::Body;
+                    }
+                }
+
+                //write out what has been read, before reading more
+                State::Body if self.pos < self.cap => {
+                    let buf = &self.buf[self.pos..self.cap];
+                    match ready_and_ok!(Pin::new(&mut *writer).poll_write(cx, buf)) {
+                        0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+                        wrote => self.pos += wrote,
+                    }
+                }
+
+                State::Body if self.remaining == 0 => self.state = State::Trailer,
+
+                State::Body => {
+                    let len = (self.remaining).min(self.buf.len());
+                    let mut read_buf = ReadBuf::new(&mut self.buf[..len]);
+                    ready_and_ok!(Pin::new(&mut *reader).poll_read(cx, &mut read_buf));
+                    match read_buf.filled().len() {
+                        0 => {
+                            let copied = self.len - self.remaining;
+                            return Poll::Ready(Err(frame::source_too_short(copied, self.len)));
+                        }
+                        read => {
+                            self.remaining -= read;
+                            self.pos = 0;
+                            self.cap = read;
+                        }
+                    }
+                }
+
+                //make sure the reader does not hold more than announced
+                State::Trailer => {
+                    let mut byte = [0; 1];
+                    let mut read_buf = ReadBuf::new(&mut byte);
+                    ready_and_ok!(Pin::new(&mut *reader).poll_read(cx, &mut read_buf));
+                    match read_buf.filled().len() {
+                        0 => self.state = State::Terminator,
+                        _ => return Poll::Ready(Err(frame::source_too_long(self.len))),
+                    }
+                }
+
+                State::Terminator => {
+                    match ready_and_ok!(Pin::new(&mut *writer).poll_write(cx, b",")) {
+                        0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+                        _ => self.state = State::Flush,
+                    }
+                }
+
+                State::Flush => return Pin::new(&mut *writer).poll_flush(cx),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+pub(crate) fn write_netstring_from_file<'a, W>(
+    writer: &'a mut W,
+    file: &'a mut File,
+) -> WriteFile<'a, W>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    WriteFile {
+        writer,
+        file,
+        transfer: None,
+        trace: FrameTrace::start(Direction::Write),
+        _pin: PhantomPinned,
+    }
+}
+
+/// Reads the metadata through a std handle of `file`, as a single `fstat` is not worth a trip
+/// to the blocking pool.
+#[cfg(feature = "fs")]
+fn metadata(file: &File) -> Result<Metadata> {
+    #[cfg(unix)]
+    let handle = std::os::unix::io::AsFd::as_fd(file).try_clone_to_owned()?;
+    #[cfg(windows)]
+    let handle = std::os::windows::io::AsHandle::as_handle(file).try_clone_to_owned()?;
+
+    std::fs::File::from(handle).metadata()
+}
+
+/// Polls the transfer of `file`, which is started with the length from its metadata on the
+/// first poll.
+#[cfg(feature = "fs")]
+fn poll_file<W>(
+    writer: &mut W,
+    file: &mut File,
+    transfer: &mut Option<Transfer>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<()>>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let transfer = match transfer {
+        Some(transfer) => transfer,
+        None => {
+            let len = frame::file_len(metadata(file)?.len())?;
+            transfer.insert(Transfer::new(len))
+        }
+    };
+    transfer.poll(writer, file, cx)
+}
+
+#[cfg(feature = "fs")]
+pin_project! {
+    /// Creates a future which will write the content of a file as one message in the netstring
+    /// format, with the length taken from the metadata of the file, and flush the writer
+    /// afterwards.
+    ///
+    /// It returns the same errors as [WriteMessageFromReader] and the errors of reading the
+    /// metadata.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WriteFile<'a, W: ?Sized> {
+        writer: &'a mut W,
+        file: &'a mut File,
+        transfer: Option<Transfer>,
+        trace: FrameTrace,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+}
+
+#[cfg(feature = "fs")]
+impl<W> Future for WriteFile<'_, W>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let me = self.project();
+        let res = {
+            let _entered = me.trace.enter();
+            ready!(poll_file(&mut **me.writer, me.file, me.transfer, cx))
+        };
+
+        let len = me.transfer.as_ref().map_or(0, |transfer| transfer.len);
+        if res.is_ok() {
+            logging::log_netstring_len(logging::WRITING, len);
+        }
+        me.trace.finish(&res, |()| len);
+        Poll::Ready(res)
+    }
+}
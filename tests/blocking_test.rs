@@ -0,0 +1,291 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, IoSlice};
+    use tokio_netstring_trait::{
+        NetstringError, NetstringRead, NetstringReadOptions, NetstringWrite, OversizePolicy,
+    };
+
+    #[test]
+    fn should_parse_netstring() {
+        let msg = "13:Hello, World!,";
+        let expected = "Hello, World!";
+        let mut buf = [0; 13];
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let len = test.read_netstring(&mut buf).expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &buf[..len]);
+    }
+
+    #[test]
+    fn should_parse_netstring_alloc() {
+        let msg = "13:Hello, World!,0:,";
+        let expected = "Hello, World!";
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let first = test.read_netstring_alloc().expect("Test should pass");
+        let second = test.read_netstring_alloc().expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &first);
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn should_drop_netstring() {
+        let msg = "13:Hello, World!,5:Hello,";
+        let expected = "Hello";
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        test.drop_netstring().expect("Test should pass");
+        let res = test.read_netstring_alloc().expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &res);
+    }
+
+    #[test]
+    fn should_fail_on_incomplete_message() {
+        let msg = "13:Hello, World!";
+        let mut buf = [0; 13];
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let err = test
+            .read_netstring(&mut buf)
+            .expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn should_fail_on_wrong_separator() {
+        let msg = "13;Hello, World!,";
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let err = test.read_netstring_alloc().expect_err("Wrong separator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&NetstringError::BadSeparator(b';')),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<NetstringError>())
+        );
+    }
+
+    #[test]
+    fn should_fail_on_message_exceeding_max_len() {
+        let msg = "13:Hello, World!,";
+        let options = NetstringReadOptions {
+            max_len: 5,
+            ..Default::default()
+        };
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let err = test
+            .read_netstring_alloc_with(options)
+            .expect_err("Message too large");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(3, test.position());
+    }
+
+    #[test]
+    fn should_resume_netstring_after_buffer_too_small() {
+        let msg = "13:Hello, World!,";
+        let mut buf = [0; 5];
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let err = test.read_netstring(&mut buf).expect_err("Buffer too small");
+
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert_eq!(
+            Some(&tokio_netstring_trait::BufferTooSmall { len: 13 }),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<tokio_netstring_trait::BufferTooSmall>())
+        );
+        assert_eq!(3, test.position());
+
+        let mut buf = [0; 13];
+        let len = test
+            .resume_netstring(&mut buf, 13)
+            .expect("Test should pass");
+
+        assert_eq!(b"Hello, World!", &buf[..len]);
+    }
+
+    #[test]
+    fn should_truncate_netstring_on_buffer_too_small() {
+        let msg = "13:Hello, World!,2:Hi,";
+        let options = NetstringReadOptions {
+            oversize: OversizePolicy::Truncate,
+            ..Default::default()
+        };
+        let mut buf = [0; 5];
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let len = test
+            .read_netstring_with(&mut buf, options)
+            .expect("Test should pass");
+        assert_eq!(13, len);
+        assert_eq!(b"Hello", &buf);
+
+        let len = test
+            .read_netstring_with(&mut buf, options)
+            .expect("Test should pass");
+        assert_eq!(b"Hi", &buf[..len]);
+    }
+
+    #[test]
+    fn should_return_none_on_eof_between_netstrings() {
+        let msg = "5:Hello,5:Hello";
+        let mut buf = [0; 13];
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        assert_eq!(
+            Some(5),
+            test.try_read_netstring(&mut buf).expect("Test should pass")
+        );
+        let err = test
+            .try_read_netstring_alloc()
+            .expect_err("Terminator missing");
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        let mut test = Cursor::new(&msg.as_bytes()[..8]);
+
+        assert_eq!(
+            Some(b"Hello".to_vec()),
+            test.try_read_netstring_alloc().expect("Test should pass")
+        );
+        assert_eq!(
+            None,
+            test.try_read_netstring(&mut buf).expect("Test should pass")
+        );
+    }
+
+    #[test]
+    fn should_write_netstring() {
+        let msg = "Hello, World!";
+        let expected = "13:Hello, World!,0:,";
+
+        let mut test = Vec::new();
+
+        test.write_netstring(msg.as_bytes()).expect("Test passes");
+        test.write_netstring(b"").expect("Test passes");
+
+        assert_eq!(expected.as_bytes(), &test[..]);
+    }
+
+    #[test]
+    fn should_write_large_netstring() {
+        let msg = vec![b'x'; 4096];
+        let mut test = Vec::new();
+
+        test.write_netstring(&msg).expect("Test should pass");
+
+        let mut test = Cursor::new(test);
+        assert_eq!(msg, test.read_netstring_alloc().expect("Test should pass"));
+    }
+
+    #[test]
+    fn should_write_netstrings() {
+        let expected = "5:Hello,0:,6:World!,";
+        let mut test = Vec::new();
+
+        test.write_netstrings(vec!["Hello", "", "World!"])
+            .expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &test[..]);
+    }
+
+    #[test]
+    fn should_write_netstring_parts() {
+        let expected = "13:Hello, World!,13:Hello, World!,";
+        let mut test = Vec::new();
+
+        test.write_netstring_parts(&[b"Hello", b", ", b"World!"])
+            .expect("Test should pass");
+        test.write_netstring_vectored(&[IoSlice::new(b"Hello, "), IoSlice::new(b"World!")])
+            .expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &test[..]);
+    }
+
+    #[test]
+    fn should_write_netstring_from_reader() {
+        let mut test = Vec::new();
+
+        test.write_netstring_from_reader(13, &mut &b"Hello, World!"[..])
+            .expect("Test should pass");
+        let err = test
+            .write_netstring_from_reader(13, &mut &b"Hello"[..])
+            .expect_err("Reader too short");
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        let err = test
+            .write_netstring_from_reader(2, &mut &b"Hello"[..])
+            .expect_err("Reader too long");
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        assert_eq!(b"13:Hello, World!,13:Hello2:He", &test[..]);
+    }
+
+    #[test]
+    fn should_write_netstring_from_file() {
+        let path = std::env::temp_dir().join("netstring_blocking_write_from_file");
+        std::fs::write(&path, b"Hello, World!").expect("Test should pass");
+        let mut file = std::fs::File::open(&path).expect("Test should pass");
+        let mut test = Vec::new();
+
+        test.write_netstring_from_file(&mut file)
+            .expect("Test should pass");
+
+        assert_eq!(b"13:Hello, World!,", &test[..]);
+        std::fs::remove_file(&path).expect("Test should pass");
+    }
+
+    #[test]
+    fn should_write_and_read_list_blocking() {
+        let mut buf = Vec::new();
+        buf.write_netstring_list(&[&b"Hello"[..], &b"World!"[..]])
+            .expect("Test should pass");
+
+        let items = Cursor::new(buf)
+            .read_netstring_list()
+            .expect("Test should pass");
+
+        assert_eq!(vec![b"Hello".to_vec(), b"World!".to_vec()], items);
+    }
+
+    #[test]
+    fn should_fail_instead_of_allocating_announced_length() {
+        let msg = format!("{}:Hello", usize::MAX);
+
+        let mut test = Cursor::new(msg.as_bytes());
+
+        let err = test
+            .read_netstring_alloc()
+            .expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn should_parse_netstring_longer_than_initial_buffer() {
+        let payload = vec![b'a'; 20_000];
+        let mut msg = b"20000:".to_vec();
+        msg.extend_from_slice(&payload);
+        msg.push(b',');
+
+        let mut test = Cursor::new(msg);
+
+        let res = test.read_netstring_alloc().expect("Test should pass");
+
+        assert_eq!(payload, res);
+    }
+}
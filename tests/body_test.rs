@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use std::time::Duration;
+    use tokio::io::AsyncReadExt;
+    use tokio_netstring_trait::{AsyncNetstringRead, NetstringError};
+    use tokio_test::io::Builder;
+
+    #[tokio::test]
+    async fn should_copy_netstring_body() {
+        let mut test = Builder::new().read(b"13:Hello, World!,5:Hello,").build();
+
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        assert_eq!(13, body.len());
+
+        let mut copied = Vec::new();
+        tokio::io::copy(&mut body, &mut copied)
+            .await
+            .expect("Test should pass");
+        assert_eq!(b"Hello, World!", &copied[..]);
+        assert_eq!(0, body.remaining());
+        drop(body);
+
+        let next = test.read_netstring_alloc().await.expect("Test should pass");
+        assert_eq!(b"Hello", &next[..]);
+    }
+
+    #[tokio::test]
+    async fn should_read_netstring_body_byte_by_byte() {
+        let msg = "13:Hello, World!,";
+        let mut test = Builder::new();
+
+        for i in 0..msg.len() {
+            test.read(&msg.as_bytes()[i..i + 1])
+                .wait(Duration::from_millis(5));
+        }
+
+        let mut test = test.build();
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        let mut copied = Vec::new();
+        body.read_to_end(&mut copied)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(b"Hello, World!", &copied[..]);
+    }
+
+    #[tokio::test]
+    async fn should_read_empty_netstring_body() {
+        let mut test = Builder::new().read(b"0:,").build();
+
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        assert!(body.is_empty());
+
+        let mut copied = Vec::new();
+        body.read_to_end(&mut copied)
+            .await
+            .expect("Test should pass");
+        assert!(copied.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_bad_terminator() {
+        let mut test = Builder::new().read(b"5:Hello;").build();
+
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        let mut copied = Vec::new();
+        let err = body
+            .read_to_end(&mut copied)
+            .await
+            .expect_err("Bad terminator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&NetstringError::BadTerminator(b';')),
+            err.get_ref().and_then(|e| e.downcast_ref())
+        );
+        assert_eq!(b"Hello", &copied[..]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_early_eof_in_body() {
+        let mut test = Builder::new().read(b"13:Hello").build();
+
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        let mut copied = Vec::new();
+        let err = body.read_to_end(&mut copied).await.expect_err("Early eof");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(
+            Some(&NetstringError::EofInBody { missing: 9 }),
+            err.get_ref().and_then(|e| e.downcast_ref())
+        );
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_invalid_header() {
+        let mut test = Builder::new().read(b"1a").build();
+
+        let err = test
+            .read_netstring_body()
+            .await
+            .expect_err("Invalid header");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_finish_netstring_body() {
+        let mut test = Builder::new().read(b"13:Hello, World!,5:Hello,").build();
+
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        let mut start = [0; 5];
+        body.read_exact(&mut start).await.expect("Test should pass");
+        assert_eq!(b"Hello", &start);
+        assert_eq!(8, body.remaining());
+        body.finish().await.expect("Test should pass");
+
+        let next = test.read_netstring_alloc().await.expect("Test should pass");
+        assert_eq!(b"Hello", &next[..]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_finish_on_bad_terminator() {
+        let mut test = Builder::new().read(b"13:Hello, World!!").build();
+
+        let body = test.read_netstring_body().await.expect("Test should pass");
+        let err = body.finish().await.expect_err("Bad terminator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_leave_reader_unusable_when_dropped_early() {
+        let mut test = &b"13:Hello, World!,5:Hello,"[..];
+
+        let mut body = AsyncNetstringRead::read_netstring_body(&mut test)
+            .await
+            .expect("Test should pass");
+        let mut start = [0; 5];
+        body.read_exact(&mut start).await.expect("Test should pass");
+        drop(body);
+
+        let err = AsyncNetstringRead::read_netstring_alloc(&mut test)
+            .await
+            .expect_err("Reader is in the middle of the payload");
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&NetstringError::InvalidLengthDigit(b',')),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<NetstringError>())
+        );
+    }
+}
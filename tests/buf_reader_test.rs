@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use tokio::io::BufReader;
+    use tokio::time::Duration;
+    use tokio_netstring_trait::{
+        AsyncNetstringBufRead, AsyncNetstringRead, NetstringReadOptions, OversizePolicy,
+    };
+    use tokio_test::io::Builder;
+
+    #[tokio::test]
+    async fn should_parse_netstring() {
+        let msg = "13:Hello, World!,";
+        let expected = "Hello, World!";
+        let mut buf = [0; 13];
+
+        let mut test = BufReader::new(Builder::new().read(msg.as_bytes()).build());
+
+        let len = test
+            .read_netstring_buffered(&mut buf)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &buf[..len]);
+    }
+
+    #[tokio::test]
+    async fn should_parse_netstrings_from_one_read() {
+        let msg = "5:Hello,0:,6:World!,";
+
+        let mut test = BufReader::new(Builder::new().read(msg.as_bytes()).build());
+
+        let mut res = Vec::new();
+        for _ in 0..3 {
+            let netstring = test
+                .read_netstring_alloc_buffered()
+                .await
+                .expect("Test should pass");
+            res.push(netstring);
+        }
+
+        assert_eq!(vec![b"Hello".to_vec(), vec![], b"World!".to_vec()], res);
+    }
+
+    #[tokio::test]
+    async fn should_parse_netstring_byte_by_byte() {
+        let msg = "13:Hello, World!,";
+        let expected = "Hello, World!";
+        let mut buf = [0; 13];
+
+        let mut builder = Builder::new();
+        for byte in msg.as_bytes() {
+            builder
+                .read(std::slice::from_ref(byte))
+                .wait(Duration::from_micros(5));
+        }
+        let mut test = BufReader::new(builder.build());
+
+        let len = test
+            .read_netstring_buffered(&mut buf)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &buf[..len]);
+    }
+
+    #[tokio::test]
+    async fn should_mix_buffered_and_unbuffered_reads() {
+        let msg = "5:Hello,6:World!,";
+        let mut buf = [0; 13];
+
+        let mut test = BufReader::new(Builder::new().read(msg.as_bytes()).build());
+
+        let len = test
+            .read_netstring_buffered(&mut buf)
+            .await
+            .expect("Test should pass");
+        assert_eq!(b"Hello", &buf[..len]);
+
+        let len = test
+            .read_netstring(&mut buf)
+            .await
+            .expect("Test should pass");
+        assert_eq!(b"World!", &buf[..len]);
+    }
+
+    #[tokio::test]
+    async fn should_skip_netstring_on_buffer_too_small() {
+        let msg = "13:Hello, World!,2:Hi,";
+        let options = NetstringReadOptions {
+            oversize: OversizePolicy::Skip,
+            ..Default::default()
+        };
+        let mut buf = [0; 5];
+
+        let mut test = BufReader::new(Builder::new().read(msg.as_bytes()).build());
+
+        let err = test
+            .read_netstring_buffered_with(&mut buf, options)
+            .await
+            .expect_err("Buffer too small");
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let len = test
+            .read_netstring_buffered_with(&mut buf, options)
+            .await
+            .expect("Test should pass");
+        assert_eq!(b"Hi", &buf[..len]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_wrong_terminator() {
+        let msg = "5:Hello;";
+
+        let mut test = BufReader::new(Builder::new().read(msg.as_bytes()).build());
+
+        let err = test
+            .read_netstring_alloc_buffered()
+            .await
+            .expect_err("Wrong terminator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_incomplete_message() {
+        let msg = "13:Hello";
+        let mut buf = [0; 13];
+
+        let mut test = BufReader::new(Builder::new().read(msg.as_bytes()).build());
+
+        let err = test
+            .read_netstring_buffered(&mut buf)
+            .await
+            .expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+}
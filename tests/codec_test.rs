@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests {
+    use bytes::{Bytes, BytesMut};
+    use futures::{SinkExt, StreamExt};
+    use std::io::ErrorKind;
+    use tokio_netstring_trait::{NetstringCodec, NetstringError, NetstringReadOptions, Strictness};
+    use tokio_test::io::Builder;
+    use tokio_util::codec::{Decoder, Encoder, Framed};
+
+    #[test]
+    fn should_decode_netstring() {
+        let mut codec = NetstringCodec::new();
+        let mut buf = BytesMut::from(&b"13:Hello, World!,5:Hello,"[..]);
+
+        let first = codec.decode(&mut buf).expect("Test should pass");
+        let second = codec.decode(&mut buf).expect("Test should pass");
+
+        assert_eq!(Some(BytesMut::from(&b"Hello, World!"[..])), first);
+        assert_eq!(Some(BytesMut::from(&b"Hello"[..])), second);
+        assert_eq!(None, codec.decode(&mut buf).expect("Test should pass"));
+    }
+
+    #[test]
+    fn should_reject_leading_zero_in_strict_mode() {
+        let mut codec = NetstringCodec::new_with_options(NetstringReadOptions {
+            strictness: Strictness::Strict,
+            ..Default::default()
+        });
+        let mut buf = BytesMut::from(&b"007:abcdefg,"[..]);
+
+        let err = codec.decode(&mut buf).expect_err("Leading zero");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&NetstringError::LeadingZero),
+            err.get_ref().and_then(|e| e.downcast_ref())
+        );
+    }
+
+    #[test]
+    fn should_decode_netstring_byte_by_byte() {
+        let msg = "13:Hello, World!,";
+        let mut codec = NetstringCodec::new();
+        let mut buf = BytesMut::new();
+
+        for byte in &msg.as_bytes()[..msg.len() - 1] {
+            buf.extend_from_slice(&[*byte]);
+            assert_eq!(None, codec.decode(&mut buf).expect("Test should pass"));
+        }
+
+        buf.extend_from_slice(b",");
+        let res = codec.decode(&mut buf).expect("Test should pass");
+
+        assert_eq!(Some(BytesMut::from(&b"Hello, World!"[..])), res);
+    }
+
+    #[test]
+    fn should_fail_on_wrong_separator() {
+        let mut codec = NetstringCodec::new();
+        let mut buf = BytesMut::from(&b"13;Hello, World!,"[..]);
+
+        let err = codec.decode(&mut buf).expect_err("Wrong separator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn should_fail_on_wrong_terminator() {
+        let mut codec = NetstringCodec::new();
+        let mut buf = BytesMut::from(&b"13:Hello, World!;"[..]);
+
+        let err = codec.decode(&mut buf).expect_err("Wrong terminator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn should_fail_on_frame_exceeding_max_length() {
+        let mut codec = NetstringCodec::new_with_max_length(5);
+        let mut buf = BytesMut::from(&b"13:"[..]);
+
+        let err = codec.decode(&mut buf).expect_err("Frame too long");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn should_fail_on_incomplete_message_at_eof() {
+        let mut codec = NetstringCodec::new();
+        let mut buf = BytesMut::from(&b"13:Hello"[..]);
+
+        let err = codec
+            .decode_eof(&mut buf)
+            .expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(
+            Some(&NetstringError::EofInBody { missing: 9 }),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<NetstringError>())
+        );
+    }
+
+    #[test]
+    fn should_not_reserve_announced_length_up_front() {
+        let mut codec = NetstringCodec::new();
+        let mut buf = BytesMut::from(&b"18446744073709551615:Hello"[..]);
+
+        assert_eq!(None, codec.decode(&mut buf).expect("Test should pass"));
+        assert!(buf.capacity() < 1024 * 1024);
+
+        let err = codec
+            .decode_eof(&mut buf)
+            .expect_err("Message not finished");
+        assert_eq!(
+            Some(&NetstringError::EofInBody {
+                missing: usize::MAX - 4
+            }),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<NetstringError>())
+        );
+    }
+
+    #[test]
+    fn should_encode_netstring() {
+        let mut codec = NetstringCodec::new();
+        let mut buf = BytesMut::new();
+
+        codec
+            .encode(&b"Hello, World!"[..], &mut buf)
+            .expect("Test should pass");
+        codec
+            .encode(Bytes::from_static(b""), &mut buf)
+            .expect("Test should pass");
+
+        assert_eq!(&b"13:Hello, World!,0:,"[..], &buf[..]);
+    }
+
+    #[test]
+    fn should_reject_encoding_frame_exceeding_max_length() {
+        let mut codec = NetstringCodec::new_with_max_length(5);
+        let mut buf = BytesMut::new();
+
+        let err = codec
+            .encode(&b"Hello, World!"[..], &mut buf)
+            .expect_err("Frame too long");
+
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert!(buf.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_read_and_write_through_framed() {
+        let test = Builder::new()
+            .read(b"13:Hello, World!,")
+            .write(b"5:Hello,")
+            .build();
+        let mut framed = Framed::new(test, NetstringCodec::new());
+
+        let res = framed.next().await.expect("Frame expected");
+        framed.send(&b"Hello"[..]).await.expect("Test should pass");
+
+        assert_eq!(&b"Hello, World!"[..], &res.expect("Test should pass")[..]);
+        assert!(framed.next().await.is_none());
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use tokio_netstring_trait::{
+        AsyncNetstringRead, DecodeEvent, NetstringDecoder, NetstringError, NetstringRead,
+        NetstringReadOptions, Strictness,
+    };
+
+    use NetstringError::*;
+
+    type Expected = Result<&'static [u8], NetstringError>;
+
+    /// The input, the result in strict mode and the result in lenient mode.
+    const CORPUS: &[(&[u8], Expected, Expected)] = &[
+        (b"0:,", Ok(b""), Ok(b"")),
+        (b"5:Hello,", Ok(b"Hello"), Ok(b"Hello")),
+        (b"1:,,", Ok(b","), Ok(b",")),
+        (b"10:0123456789,", Ok(b"0123456789"), Ok(b"0123456789")),
+        (b"007:abcdefg,", Err(LeadingZero), Ok(b"abcdefg")),
+        (b"00:,", Err(LeadingZero), Ok(b"")),
+        (
+            b"00000000000000000005:Hello,",
+            Err(LeadingZero),
+            Ok(b"Hello"),
+        ),
+        (
+            b"0000000000000000000000005:Hello,",
+            Err(LeadingZero),
+            Err(LengthOverflow),
+        ),
+        (
+            b":,",
+            Err(InvalidLengthDigit(b':')),
+            Err(InvalidLengthDigit(b':')),
+        ),
+        (
+            b"-1:,",
+            Err(InvalidLengthDigit(b'-')),
+            Err(InvalidLengthDigit(b'-')),
+        ),
+        (
+            b"+5:Hello,",
+            Err(InvalidLengthDigit(b'+')),
+            Err(InvalidLengthDigit(b'+')),
+        ),
+        (
+            b" 5:Hello,",
+            Err(InvalidLengthDigit(b' ')),
+            Err(InvalidLengthDigit(b' ')),
+        ),
+        (
+            b"5 :Hello,",
+            Err(BadSeparator(b' ')),
+            Err(BadSeparator(b' ')),
+        ),
+        (b"5x", Err(BadSeparator(b'x')), Err(BadSeparator(b'x'))),
+        (
+            b"0x5:Hello,",
+            Err(BadSeparator(b'x')),
+            Err(BadSeparator(b'x')),
+        ),
+        (
+            b"5:Hello;",
+            Err(BadTerminator(b';')),
+            Err(BadTerminator(b';')),
+        ),
+        (
+            b"5:Hello",
+            Err(EofInBody { missing: 1 }),
+            Err(EofInBody { missing: 1 }),
+        ),
+        (
+            b"5:Hel",
+            Err(EofInBody { missing: 3 }),
+            Err(EofInBody { missing: 3 }),
+        ),
+        (b"", Err(EofInHeader), Err(EofInHeader)),
+        (b"5", Err(EofInHeader), Err(EofInHeader)),
+        (
+            b"123456789012345678901:",
+            Err(LengthOverflow),
+            Err(LengthOverflow),
+        ),
+    ];
+
+    fn options(strictness: Strictness) -> NetstringReadOptions {
+        NetstringReadOptions {
+            strictness,
+            ..Default::default()
+        }
+    }
+
+    fn decode(mut input: &[u8], strictness: Strictness) -> Result<Vec<u8>, NetstringError> {
+        let mut decoder = NetstringDecoder::with_options(options(strictness));
+        let mut payload = Vec::new();
+
+        while !input.is_empty() {
+            let (consumed, event) = decoder.decode(input);
+            input = &input[consumed..];
+
+            match event {
+                Some(DecodeEvent::Body(chunk)) => payload.extend_from_slice(chunk),
+                Some(DecodeEvent::Complete) => return Ok(payload),
+                Some(DecodeEvent::Error(err)) => return Err(err),
+                _ => {}
+            }
+        }
+
+        Err(decoder.eof_error())
+    }
+
+    fn netstring_error(err: std::io::Error) -> NetstringError {
+        *err.get_ref()
+            .and_then(|e| e.downcast_ref::<NetstringError>())
+            .expect("Error should wrap a NetstringError")
+    }
+
+    fn cases() -> impl Iterator<Item = (&'static [u8], Strictness, Expected)> {
+        CORPUS.iter().flat_map(|(input, strict, lenient)| {
+            IntoIterator::into_iter([
+                (*input, Strictness::Strict, *strict),
+                (*input, Strictness::Lenient, *lenient),
+            ])
+        })
+    }
+
+    #[test]
+    fn should_decode_corpus() {
+        for (input, strictness, expected) in cases() {
+            let res = decode(input, strictness);
+
+            assert_eq!(
+                expected.map(<[u8]>::to_vec),
+                res,
+                "{:?} in {:?} mode",
+                String::from_utf8_lossy(input),
+                strictness
+            );
+        }
+    }
+
+    #[test]
+    fn should_read_corpus_blocking() {
+        for (input, strictness, expected) in cases() {
+            let mut reader = Cursor::new(input);
+            let res = NetstringRead::read_netstring_alloc_with(&mut reader, options(strictness))
+                .map_err(netstring_error);
+
+            assert_eq!(
+                expected.map(<[u8]>::to_vec),
+                res,
+                "{:?} in {:?} mode",
+                String::from_utf8_lossy(input),
+                strictness
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn should_read_corpus() {
+        for (input, strictness, expected) in cases() {
+            let mut reader = input;
+            let res =
+                AsyncNetstringRead::read_netstring_alloc_with(&mut reader, options(strictness))
+                    .await
+                    .map_err(netstring_error);
+
+            assert_eq!(
+                expected.map(<[u8]>::to_vec),
+                res,
+                "{:?} in {:?} mode",
+                String::from_utf8_lossy(input),
+                strictness
+            );
+        }
+    }
+}
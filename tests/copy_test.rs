@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+    use tokio::io::{AsyncRead, ReadBuf};
+    use tokio_netstring_trait::{copy_netstring, encode_to_vec, relay_netstrings, NetstringError};
+    use tokio_test::io::Builder;
+
+    #[tokio::test]
+    async fn should_copy_netstring() {
+        let mut reader = Builder::new().read(b"13:Hello, World!,").build();
+        let mut writer = Vec::new();
+
+        let len = copy_netstring(&mut reader, &mut writer)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(13, len);
+        assert_eq!(b"13:Hello, World!,", &writer[..]);
+    }
+
+    #[tokio::test]
+    async fn should_copy_netstring_byte_by_byte() {
+        let msg = "13:Hello, World!,";
+        let mut reader = Builder::new();
+        let mut writer = Builder::new();
+
+        for i in 0..msg.len() {
+            reader
+                .read(&msg.as_bytes()[i..i + 1])
+                .wait(Duration::from_millis(5));
+        }
+        writer.write(b"13:");
+        for i in 3..msg.len() {
+            writer.write(&msg.as_bytes()[i..i + 1]);
+        }
+
+        let len = copy_netstring(&mut reader.build(), &mut writer.build())
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(13, len);
+    }
+
+    #[tokio::test]
+    async fn should_copy_large_netstring_in_chunks() {
+        let mut msg = Vec::new();
+        encode_to_vec(&vec![b'x'; 20_000], &mut msg);
+        let mut writer = Vec::new();
+
+        let len = copy_netstring(&mut &msg[..], &mut writer)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(20_000, len);
+        assert_eq!(msg, writer);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_copy_on_bad_terminator() {
+        let mut reader = Builder::new().read(b"5:Hello;").build();
+        let mut writer = Vec::new();
+
+        let err = copy_netstring(&mut reader, &mut writer)
+            .await
+            .expect_err("Bad terminator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&NetstringError::BadTerminator(b';')),
+            err.get_ref().and_then(|e| e.downcast_ref())
+        );
+        assert_eq!(b"5:Hello", &writer[..]);
+    }
+
+    #[tokio::test]
+    async fn should_relay_netstrings() {
+        let reader = Builder::new().read(b"5:Hello,0:,6:World!,").build();
+
+        let mut relay = relay_netstrings(reader, Vec::new());
+        let mut lens = Vec::new();
+        while let Some(len) = relay.next_frame().await.expect("Test should pass") {
+            lens.push(len);
+        }
+
+        assert_eq!(vec![5, 0, 6], lens);
+        assert_eq!(b"5:Hello,0:,6:World!,", &relay.into_inner().1[..]);
+    }
+
+    /// Returns `Pending` before every byte it reads.
+    struct PendingReader {
+        data: &'static [u8],
+        pending: bool,
+    }
+
+    impl AsyncRead for PendingReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            if let Some((byte, rest)) = self.data.split_first() {
+                buf.put_slice(&[*byte]);
+                self.data = rest;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn should_relay_netstrings_from_pending_reader() {
+        let msg = b"5:Hello,2:Hi,";
+        let reader = PendingReader {
+            data: msg,
+            pending: false,
+        };
+        let mut relay = relay_netstrings(reader, Vec::new());
+
+        assert_eq!(Some(5), relay.next_frame().await.expect("Test should pass"));
+        assert_eq!(Some(2), relay.next_frame().await.expect("Test should pass"));
+        assert_eq!(None, relay.next_frame().await.expect("Test should pass"));
+
+        assert_eq!(&msg[..], &relay.into_inner().1[..]);
+    }
+
+    #[tokio::test]
+    async fn should_stop_relay_on_framing_error() {
+        let reader = Builder::new().read(b"5:Hello,5x").build();
+
+        let mut relay = relay_netstrings(reader, Vec::new());
+
+        assert_eq!(Some(5), relay.next_frame().await.expect("Test should pass"));
+        let err = relay.next_frame().await.expect_err("Invalid header");
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(None, relay.next_frame().await.expect("Test should pass"));
+        assert_eq!(b"5:Hello,", &relay.into_inner().1[..]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_relay_on_early_eof() {
+        let reader = Builder::new().read(b"5:Hel").build();
+
+        let mut relay = relay_netstrings(reader, Vec::new());
+
+        let err = relay.next_frame().await.expect_err("Early eof");
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(None, relay.next_frame().await.expect("Test should pass"));
+    }
+}
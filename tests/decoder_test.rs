@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+    use tokio_netstring_trait::{
+        DecodeEvent, NetstringDecoder, NetstringError, NetstringReadOptions,
+    };
+
+    fn decode_all<'a>(decoder: &mut NetstringDecoder, mut input: &'a [u8]) -> Vec<DecodeEvent<'a>> {
+        let mut events = Vec::new();
+        while !input.is_empty() {
+            let (consumed, event) = decoder.decode(input);
+            input = &input[consumed..];
+            match event {
+                Some(DecodeEvent::Error(err)) => {
+                    events.push(DecodeEvent::Error(err));
+                    break;
+                }
+                Some(event) => events.push(event),
+                None => {}
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn should_decode_netstring() {
+        let msg = b"13:Hello, World!,";
+        let mut decoder = NetstringDecoder::new();
+
+        let events = decode_all(&mut decoder, msg);
+
+        assert_eq!(
+            vec![
+                DecodeEvent::Length(13),
+                DecodeEvent::Body(b"Hello, World!"),
+                DecodeEvent::Complete
+            ],
+            events
+        );
+        assert!(decoder.is_at_boundary());
+    }
+
+    #[test]
+    fn should_decode_netstring_byte_by_byte() {
+        let msg = b"13:Hello, World!,";
+        let mut decoder = NetstringDecoder::new();
+        let mut body = Vec::new();
+        let mut completed = false;
+
+        for byte in msg.chunks(1) {
+            match decoder.decode(byte) {
+                (1, Some(DecodeEvent::Body(chunk))) => body.extend_from_slice(chunk),
+                (1, Some(DecodeEvent::Complete)) => completed = true,
+                (1, _) => {}
+                res => panic!("Unexpected result {:?}", res),
+            }
+        }
+
+        assert_eq!(b"Hello, World!".to_vec(), body);
+        assert!(completed);
+    }
+
+    #[test]
+    fn should_stop_body_at_terminator() {
+        let msg = b"5:Hello,5:World,";
+        let mut decoder = NetstringDecoder::new();
+
+        assert_eq!((2, Some(DecodeEvent::Length(5))), decoder.decode(msg));
+        assert_eq!(5, decoder.body_remaining());
+        assert_eq!(
+            (5, Some(DecodeEvent::Body(&b"Hello"[..]))),
+            decoder.decode(&msg[2..])
+        );
+        assert_eq!((1, Some(DecodeEvent::Complete)), decoder.decode(&msg[7..]));
+        assert_eq!(
+            vec![
+                DecodeEvent::Length(5),
+                DecodeEvent::Body(b"World"),
+                DecodeEvent::Complete
+            ],
+            decode_all(&mut decoder, &msg[8..])
+        );
+    }
+
+    #[test]
+    fn should_decode_zero_length_netstring() {
+        let mut decoder = NetstringDecoder::new();
+
+        let events = decode_all(&mut decoder, b"0:,");
+
+        assert_eq!(vec![DecodeEvent::Length(0), DecodeEvent::Complete], events);
+    }
+
+    #[test]
+    fn should_fail_on_invalid_length() {
+        let mut decoder = NetstringDecoder::new();
+
+        let events = decode_all(&mut decoder, b"a:,");
+
+        assert_eq!(
+            vec![DecodeEvent::Error(NetstringError::InvalidLengthDigit(b'a'))],
+            events
+        );
+    }
+
+    #[test]
+    fn should_fail_on_empty_length() {
+        let mut decoder = NetstringDecoder::new();
+
+        let events = decode_all(&mut decoder, b":,");
+
+        assert_eq!(
+            vec![DecodeEvent::Error(NetstringError::InvalidLengthDigit(b':'))],
+            events
+        );
+    }
+
+    #[test]
+    fn should_fail_on_length_overflow() {
+        let mut decoder = NetstringDecoder::new();
+
+        let events = decode_all(&mut decoder, b"99999999999999999999999:");
+
+        assert_eq!(
+            vec![DecodeEvent::Error(NetstringError::LengthOverflow)],
+            events
+        );
+    }
+
+    #[test]
+    fn should_fail_on_wrong_separator() {
+        let mut decoder = NetstringDecoder::new();
+
+        let events = decode_all(&mut decoder, b"5;Hello,");
+
+        assert_eq!(
+            vec![DecodeEvent::Error(NetstringError::BadSeparator(b';'))],
+            events
+        );
+    }
+
+    #[test]
+    fn should_keep_failing_after_wrong_terminator() {
+        let mut decoder = NetstringDecoder::new();
+
+        let events = decode_all(&mut decoder, b"5:Hello;");
+
+        assert_eq!(
+            DecodeEvent::Error(NetstringError::BadTerminator(b';')),
+            events[2]
+        );
+        assert_eq!(
+            (
+                0,
+                Some(DecodeEvent::Error(NetstringError::BadTerminator(b';')))
+            ),
+            decoder.decode(b"0:,")
+        );
+    }
+
+    #[test]
+    fn should_fail_on_message_exceeding_max_len() {
+        let mut decoder = NetstringDecoder::with_options(NetstringReadOptions {
+            max_len: 5,
+            ..Default::default()
+        });
+
+        let events = decode_all(&mut decoder, b"5:Hello,6:Hello!,");
+
+        assert_eq!(
+            DecodeEvent::Error(NetstringError::TooLarge { len: 6, max: 5 }),
+            events[3]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use tokio_netstring_trait::AsyncNetstringRead;
+    use tokio_test::io::Builder;
+
+    #[tokio::test]
+    async fn should_drop_netstring() {
+        let msg = "13:Hello, World!,5:Hello,";
+        let expected = "Hello";
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        test.drop_netstring().await.expect("Test should pass");
+        let res = test.read_netstring_alloc().await.expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &res);
+    }
+
+    #[tokio::test]
+    async fn should_drop_netstring_byte_by_byte() {
+        let msg = "13:Hello, World!,";
+
+        let mut test = Builder::new();
+
+        for i in 0..msg.len() {
+            test.read(&msg.as_bytes()[i..i + 1])
+                .wait(Duration::from_micros(5));
+        }
+
+        test.build()
+            .drop_netstring()
+            .await
+            .expect("Test should pass");
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_incomplete_message() {
+        let msg = "13:Hello, World!,";
+        let split = 10;
+
+        let mut test = Builder::new().read(&msg.as_bytes()[..split]).build();
+
+        test.drop_netstring()
+            .await
+            .expect_err("Message not finished");
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_wrong_terminator() {
+        let msg = "13:Hello, World!;";
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        test.drop_netstring().await.expect_err("Wrong terminator");
+    }
+}
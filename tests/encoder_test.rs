@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use tokio_netstring_trait::{
+        encode_header, encode_to_slice, encode_to_vec, encoded_len, MAX_HEADER_LENGTH,
+    };
+
+    #[test]
+    fn should_encode_header() {
+        let mut buf = [0; MAX_HEADER_LENGTH];
+
+        assert_eq!(b"13:", encode_header(13, &mut buf));
+        assert_eq!(b"0:", encode_header(0, &mut buf));
+        assert_eq!(
+            format!("{}:", usize::MAX).as_bytes(),
+            encode_header(usize::MAX, &mut buf)
+        );
+    }
+
+    #[test]
+    fn should_compute_encoded_len() {
+        assert_eq!(3, encoded_len(0));
+        assert_eq!(17, encoded_len(13));
+        assert_eq!(105, encoded_len(100));
+    }
+
+    #[test]
+    fn should_encode_to_slice() {
+        let msg = "Hello, World!";
+        let expected = "13:Hello, World!,";
+        let mut buf = [0; 32];
+
+        let len = encode_to_slice(msg.as_bytes(), &mut buf).expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &buf[..len]);
+    }
+
+    #[test]
+    fn should_not_encode_to_short_slice() {
+        let msg = "Hello, World!";
+        let mut buf = [0; 16];
+
+        assert_eq!(None, encode_to_slice(msg.as_bytes(), &mut buf));
+        assert_eq!([0; 16], buf);
+    }
+
+    #[test]
+    fn should_encode_to_vec() {
+        let expected = "13:Hello, World!,0:,";
+        let mut buf = Vec::new();
+
+        encode_to_vec(b"Hello, World!", &mut buf);
+        encode_to_vec(b"", &mut buf);
+
+        assert_eq!(expected.as_bytes(), &buf[..]);
+    }
+}
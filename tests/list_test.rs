@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use tokio_netstring_trait::{
+        decode_list, encode_list_to_slice, encode_list_to_vec, encoded_list_len,
+        AsyncNetstringRead, AsyncNetstringWrite, NetstringError,
+    };
+    use tokio_test::io::Builder;
+
+    #[test]
+    fn should_encode_list_to_slice() {
+        let items: [&[u8]; 3] = [b"Hello", b"", b"World!"];
+        let expected = "20:5:Hello,0:,6:World!,,";
+        let mut buf = [0; 32];
+
+        let len = encode_list_to_slice(&items, &mut buf).expect("Test should pass");
+
+        assert_eq!(expected.len(), encoded_list_len(&items));
+        assert_eq!(expected.as_bytes(), &buf[..len]);
+    }
+
+    #[test]
+    fn should_not_encode_list_to_short_slice() {
+        let mut buf = [0; 8];
+
+        assert_eq!(None, encode_list_to_slice(&[&b"Hello"[..]], &mut buf));
+        assert_eq!([0; 8], buf);
+    }
+
+    #[test]
+    fn should_encode_empty_list() {
+        let mut buf = Vec::new();
+
+        encode_list_to_vec(&[], &mut buf);
+
+        assert_eq!(b"0:,", &buf[..]);
+    }
+
+    #[test]
+    fn should_decode_list() {
+        let items = decode_list(b"5:Hello,0:,6:World!,")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Test should pass");
+
+        assert_eq!(vec![&b"Hello"[..], &b""[..], &b"World!"[..]], items);
+    }
+
+    #[test]
+    fn should_decode_empty_list() {
+        assert_eq!(None, decode_list(b"").next());
+    }
+
+    #[test]
+    fn should_fail_on_truncated_list() {
+        let mut items = decode_list(b"5:Hello,6:Wor");
+
+        assert_eq!(Some(Ok(&b"Hello"[..])), items.next());
+        assert_eq!(
+            Some(Err(NetstringError::EofInBody { missing: 4 })),
+            items.next()
+        );
+        assert_eq!(None, items.next());
+    }
+
+    #[test]
+    fn should_fail_on_trailing_garbage_in_list() {
+        let mut items = decode_list(b"5:Hello,x");
+
+        assert_eq!(Some(Ok(&b"Hello"[..])), items.next());
+        assert_eq!(
+            Some(Err(NetstringError::InvalidLengthDigit(b'x'))),
+            items.next()
+        );
+        assert_eq!(None, items.next());
+    }
+
+    #[tokio::test]
+    async fn should_write_and_read_list() {
+        let mut buf = Vec::new();
+        buf.write_netstring_list(&[&b"CONTENT_LENGTH"[..], &b"0"[..]])
+            .await
+            .expect("Test should pass");
+        assert_eq!(b"22:14:CONTENT_LENGTH,1:0,,", &buf[..]);
+
+        let items = (&buf[..])
+            .read_netstring_list()
+            .await
+            .expect("Test should pass");
+        assert_eq!(vec![b"CONTENT_LENGTH".to_vec(), b"0".to_vec()], items);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_read_list_with_truncated_item() {
+        let mut test = Builder::new().read(b"7:5:Hello,").build();
+
+        let err = test
+            .read_netstring_list()
+            .await
+            .expect_err("Truncated item");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&NetstringError::EofInBody { missing: 1 }),
+            err.get_ref().and_then(|e| e.downcast_ref())
+        );
+    }
+}
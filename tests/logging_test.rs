@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use std::io::Cursor;
+    use std::sync::Mutex;
+    use tokio::io::AsyncReadExt;
+    use tokio_netstring_trait::{
+        payload_logging, set_payload_logging, AsyncNetstringRead, AsyncNetstringWrite,
+        NetstringRead, NetstringWrite, PayloadLogging,
+    };
+
+    /// Records the trace messages of this crate.
+    struct RecordingLogger {
+        records: Mutex<Vec<String>>,
+    }
+
+    impl Log for RecordingLogger {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.level() <= Level::Trace
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            if record.target().starts_with("tokio_netstring_trait") {
+                let msg = record.args().to_string();
+                self.records.lock().expect("Lock not poisoned").push(msg);
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: RecordingLogger = RecordingLogger {
+        records: Mutex::new(Vec::new()),
+    };
+
+    fn take_records() -> Vec<String> {
+        std::mem::take(&mut *LOGGER.records.lock().expect("Lock not poisoned"))
+    }
+
+    fn write_and_read(msg: &[u8]) -> Vec<String> {
+        let mut buf = Vec::new();
+        NetstringWrite::write_netstring(&mut buf, msg).expect("Test should pass");
+        NetstringRead::read_netstring_alloc(&mut Cursor::new(buf)).expect("Test should pass");
+        take_records()
+    }
+
+    // All cases share the global logger and logging mode, so they run in one test.
+    #[tokio::test]
+    async fn should_log_payloads_according_to_mode() {
+        log::set_logger(&LOGGER).expect("Test should pass");
+        log::set_max_level(LevelFilter::Trace);
+        let binary = b"\x00\xffHi\n";
+
+        assert_eq!(PayloadLogging::LengthOnly, payload_logging());
+        assert_eq!(
+            vec!["WRITING NETSTRING: 5 bytes", "READING NETSTRING: 5 bytes"],
+            write_and_read(binary)
+        );
+
+        set_payload_logging(PayloadLogging::Escaped { max_bytes: 16 });
+        assert_eq!(PayloadLogging::Escaped { max_bytes: 16 }, payload_logging());
+        assert_eq!(
+            vec![
+                "WRITING NETSTRING: 5:\\x00\\xffHi\\n,",
+                "READING NETSTRING: 5:\\x00\\xffHi\\n,"
+            ],
+            write_and_read(binary)
+        );
+
+        set_payload_logging(PayloadLogging::Hex { max_bytes: 2 });
+        assert_eq!(
+            vec![
+                "WRITING NETSTRING: 5:00ff...,",
+                "READING NETSTRING: 5:00ff...,"
+            ],
+            write_and_read(binary)
+        );
+
+        set_payload_logging(PayloadLogging::Redacted);
+        assert_eq!(
+            vec![
+                "WRITING NETSTRING: <redacted>",
+                "READING NETSTRING: <redacted>"
+            ],
+            write_and_read(b"secret")
+        );
+
+        set_payload_logging(PayloadLogging::Off);
+        assert!(write_and_read(b"secret").is_empty());
+
+        set_payload_logging(PayloadLogging::Escaped { max_bytes: 4 });
+        let mut buf = Vec::new();
+        let items: [&[u8]; 2] = [b"a", b"b"];
+        AsyncNetstringWrite::write_netstring_list(&mut buf, &items)
+            .await
+            .expect("Test should pass");
+        AsyncNetstringRead::drop_netstring(&mut &buf[..])
+            .await
+            .expect("Test should pass");
+        assert_eq!(
+            vec![
+                "WRITING NETSTRING: 8:1:a,...,",
+                "DROPPING NETSTRING: 8 bytes"
+            ],
+            take_records()
+        );
+
+        let mut test = &b"5:Hello,"[..];
+        let mut body = AsyncNetstringRead::read_netstring_body(&mut test)
+            .await
+            .expect("Test should pass");
+        let mut buf = [0; 2];
+        body.read_exact(&mut buf).await.expect("Test should pass");
+        drop(body);
+        assert_eq!(
+            vec![
+                "READING NETSTRING: 5 bytes",
+                "NETSTRING BODY DROPPED: 3 bytes of the payload and the terminator were not read"
+            ],
+            take_records()
+        );
+
+        #[cfg(feature = "stream")]
+        {
+            use futures::SinkExt;
+
+            set_payload_logging(PayloadLogging::Escaped { max_bytes: 16 });
+            let mut sink = tokio_netstring_trait::NetstringSink::new(Vec::new());
+            sink.send("Hi").await.expect("Test should pass");
+            assert_eq!(vec!["WRITING NETSTRING: 2:Hi,"], take_records());
+        }
+
+        let mut full = [0; 2];
+        NetstringWrite::write_netstring(&mut &mut full[..], b"Hello").expect_err("Writer full");
+        let mut buf = Vec::new();
+        drop(AsyncNetstringWrite::write_netstring(&mut buf, b"Hello"));
+        assert!(take_records().is_empty());
+
+        set_payload_logging(PayloadLogging::LengthOnly);
+    }
+}
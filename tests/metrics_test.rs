@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio_netstring_trait::{
+        copy_netstring, set_metrics, AsyncNetstringRead, AsyncNetstringWrite, AtomicMetrics,
+        MetricsSnapshot, NetstringMetrics, NetstringRead, NetstringReadOptions, NetstringWrite,
+        Strictness,
+    };
+
+    static METRICS: AtomicMetrics = AtomicMetrics::new();
+
+    /// Only overrides one method, relying on the no-op defaults for the others.
+    struct FrameCounter(AtomicUsize);
+
+    impl NetstringMetrics for FrameCounter {
+        fn frame_read(&self, _: usize) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    static COUNTER: FrameCounter = FrameCounter(AtomicUsize::new(0));
+
+    // All cases share the global metrics, so they run in one test.
+    #[tokio::test]
+    async fn should_count_frames_bytes_and_errors() {
+        set_metrics(&METRICS).expect("Test should pass");
+        assert_eq!(MetricsSnapshot::default(), METRICS.snapshot());
+
+        let mut test = tokio_test::io::Builder::new()
+            .read(b"5:Hello,")
+            .read(b"3:abc,")
+            .write(b"6:World!,")
+            .build();
+        test.read_netstring_alloc().await.expect("Test should pass");
+        test.drop_netstring().await.expect("Test should pass");
+        test.write_netstring(b"World!")
+            .await
+            .expect("Test should pass");
+
+        let snapshot = METRICS.snapshot();
+        assert_eq!(1, snapshot.frames_read);
+        assert_eq!(1, snapshot.frames_dropped);
+        assert_eq!(1, snapshot.frames_written);
+        assert_eq!(8 + 6, snapshot.bytes_read);
+        assert_eq!(9, snapshot.bytes_written);
+
+        let mut buf = Vec::new();
+        NetstringWrite::write_netstring(&mut buf, b"Hi").expect("Test passes");
+        NetstringRead::read_netstring_alloc(&mut Cursor::new(buf)).expect("Test should pass");
+
+        let snapshot = METRICS.snapshot();
+        assert_eq!(2, snapshot.frames_read);
+        assert_eq!(2, snapshot.frames_written);
+        assert_eq!(14 + 5, snapshot.bytes_read);
+        assert_eq!(9 + 5, snapshot.bytes_written);
+
+        let options = NetstringReadOptions {
+            max_len: 3,
+            ..Default::default()
+        };
+        let mut test = Cursor::new(b"5:Hello,".to_vec());
+        let err = NetstringRead::read_netstring_alloc_with(&mut test, options)
+            .expect_err("Message too large");
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+
+        let mut buf = [0; 2];
+        let mut test = tokio_test::io::Builder::new().read(b"5:").build();
+        let err = test
+            .read_netstring(&mut buf)
+            .await
+            .expect_err("Buffer too small");
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert_eq!(2, METRICS.snapshot().oversize_rejected);
+
+        let strict = NetstringReadOptions {
+            strictness: Strictness::Strict,
+            ..Default::default()
+        };
+        for (input, options) in [
+            (&b"5;"[..], NetstringReadOptions::default()),
+            (&b"01:"[..], strict),
+            (&b"5:He"[..], NetstringReadOptions::default()),
+        ] {
+            NetstringRead::read_netstring_alloc_with(&mut Cursor::new(input), options)
+                .expect_err("Invalid netstring");
+        }
+
+        let snapshot = METRICS.snapshot();
+        assert_eq!(1, snapshot.bad_separator);
+        assert_eq!(1, snapshot.leading_zero);
+        assert_eq!(1, snapshot.eof_in_body);
+        assert_eq!(0, snapshot.eof_in_header);
+        assert_eq!(2, snapshot.frames_read);
+        assert_eq!(19 + 2 + 2 + 2 + 2 + 4, snapshot.bytes_read);
+
+        let mut test = Cursor::new(b"2:Hi,");
+        while NetstringRead::try_read_netstring_alloc(&mut test)
+            .expect("Test should pass")
+            .is_some()
+        {}
+        let mut test = tokio_test::io::Builder::new().read(b"2:Hi,").build();
+        while AsyncNetstringRead::try_read_netstring_alloc(&mut test)
+            .await
+            .expect("Test should pass")
+            .is_some()
+        {}
+        NetstringRead::read_netstring_alloc(&mut Cursor::new(b"12")).expect_err("Header cut");
+
+        let snapshot = METRICS.snapshot();
+        assert_eq!(4, snapshot.frames_read);
+        assert_eq!(1, snapshot.eof_in_header);
+
+        let mut test = tokio_test::io::Builder::new()
+            .read(b"2:Hi,")
+            .read(b"3:abc,")
+            .build();
+        let mut copied = Vec::new();
+        copy_netstring(&mut test, &mut copied)
+            .await
+            .expect("Test should pass");
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        tokio::io::copy(&mut body, &mut tokio::io::sink())
+            .await
+            .expect("Test should pass");
+        NetstringRead::read_netstring_alloc(&mut Cursor::new(b"005:Hello,"))
+            .expect("Test should pass");
+
+        let before = snapshot;
+        let snapshot = METRICS.snapshot();
+        assert_eq!(before.frames_read + 3, snapshot.frames_read);
+        assert_eq!(before.frames_written + 1, snapshot.frames_written);
+        assert_eq!(before.bytes_read + 5 + 6 + 10, snapshot.bytes_read);
+        assert_eq!(before.bytes_written + 5, snapshot.bytes_written);
+
+        set_metrics(&COUNTER).expect_err("Metrics already installed");
+        NetstringRead::read_netstring_alloc(&mut Cursor::new(b"0:,")).expect("Test should pass");
+        assert_eq!(0, COUNTER.0.load(Ordering::Relaxed));
+        assert_eq!(8, METRICS.snapshot().frames_read);
+
+        COUNTER.bytes_read(3);
+        COUNTER.frame_read(0);
+        assert_eq!(1, COUNTER.0.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use tokio::io::AsyncWriteExt;
+    use tokio_netstring_trait::{NetstringError, NetstringReadOptions, NetstringReader};
+
+    #[tokio::test]
+    async fn should_read_netstrings_until_eof() {
+        let test = tokio_test::io::Builder::new()
+            .read(b"5:He")
+            .read(b"llo,0:,6:Wor")
+            .read(b"ld!,")
+            .build();
+        let mut reader = NetstringReader::new(test);
+
+        let mut frames = Vec::new();
+        while let Some(frame) = reader.next_frame().await.expect("Test should pass") {
+            frames.push(frame);
+        }
+
+        assert_eq!(
+            vec![b"Hello".to_vec(), Vec::new(), b"World!".to_vec()],
+            frames
+        );
+        assert_eq!(None, reader.next_frame().await.expect("Test should pass"));
+    }
+
+    #[tokio::test]
+    async fn should_keep_partial_netstring_when_cancelled_in_select() {
+        let (mut client, server) = tokio::io::duplex(64);
+        let mut reader = NetstringReader::new(server);
+
+        client
+            .write_all(b"13:Hello,")
+            .await
+            .expect("Test should pass");
+        for _ in 0..3 {
+            tokio::select! {
+                biased;
+                frame = reader.next_frame() => panic!("Netstring incomplete: {:?}", frame),
+                _ = tokio::task::yield_now() => {}
+            }
+        }
+
+        client
+            .write_all(b" World!,2:Hi,")
+            .await
+            .expect("Test should pass");
+        drop(client);
+
+        let frame = reader.next_frame().await.expect("Test should pass");
+        assert_eq!(Some(b"Hello, World!".to_vec()), frame);
+        let frame = reader.next_frame().await.expect("Test should pass");
+        assert_eq!(Some(b"Hi".to_vec()), frame);
+        assert_eq!(None, reader.next_frame().await.expect("Test should pass"));
+    }
+
+    #[tokio::test]
+    async fn should_end_after_invalid_netstring() {
+        let test = tokio_test::io::Builder::new().read(b"5;").build();
+        let mut reader = NetstringReader::new(test);
+
+        let err = reader.next_frame().await.expect_err("Wrong separator");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            Some(&NetstringError::BadSeparator(b';')),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<NetstringError>())
+        );
+        assert_eq!(None, reader.next_frame().await.expect("Test should pass"));
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_eof_in_netstring() {
+        let test = tokio_test::io::Builder::new().read(b"5:Hello").build();
+        let mut reader = NetstringReader::new(test);
+
+        let err = reader.next_frame().await.expect_err("Terminator missing");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_netstring_exceeding_max_len() {
+        let options = NetstringReadOptions {
+            max_len: 3,
+            ..Default::default()
+        };
+        let test = tokio_test::io::Builder::new().read(b"2:Hi,5:").build();
+        let mut reader = NetstringReader::with_options(test, options);
+
+        let frame = reader.next_frame().await.expect("Test should pass");
+        assert_eq!(Some(b"Hi".to_vec()), frame);
+        let err = reader.next_frame().await.expect_err("Message too large");
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_keep_bytes_of_next_netstring_in_buffer() {
+        let test = tokio_test::io::Builder::new().read(b"2:Hi,3:ab").build();
+        let mut reader = NetstringReader::new(test);
+
+        let frame = reader.next_frame().await.expect("Test should pass");
+
+        assert_eq!(Some(b"Hi".to_vec()), frame);
+        assert_eq!(b"3:ab", reader.buffer());
+    }
+
+    #[tokio::test]
+    async fn should_fail_instead_of_allocating_announced_length() {
+        let msg = format!("{}:Hello", usize::MAX);
+        let test = tokio_test::io::Builder::new().read(msg.as_bytes()).build();
+        let mut reader = NetstringReader::new(test);
+
+        let err = reader.next_frame().await.expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+}
@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use tokio_netstring_trait::AsyncNetstringRead;
+    use std::io::ErrorKind;
     use tokio::time::Duration;
+    use tokio_netstring_trait::{AsyncNetstringRead, NetstringReadOptions};
     use tokio_test::io::Builder;
 
     #[tokio::test]
@@ -77,4 +78,105 @@ mod tests {
             .await
             .expect_err("Message not finished");
     }
+
+    #[tokio::test]
+    async fn should_parse_netstring_within_max_len() {
+        let msg = "13:Hello, World!,";
+        let expected = "Hello, World!";
+        let options = NetstringReadOptions {
+            max_len: 13,
+            ..Default::default()
+        };
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let res = test
+            .read_netstring_alloc_with(options)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &res);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_message_exceeding_max_len_after_header() {
+        let msg = "1000000000:";
+        let options = NetstringReadOptions {
+            max_len: 1024,
+            ..Default::default()
+        };
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .read_netstring_alloc_with(options)
+            .await
+            .expect_err("Message too large");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_return_none_on_eof_between_netstrings() {
+        let msg = "5:Hello,";
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let res = test
+            .try_read_netstring_alloc()
+            .await
+            .expect("Test should pass");
+        assert_eq!(Some(b"Hello".to_vec()), res);
+
+        let res = test
+            .try_read_netstring_alloc()
+            .await
+            .expect("Test should pass");
+        assert_eq!(None, res);
+    }
+
+    #[tokio::test]
+    async fn should_fail_try_read_on_eof_in_body() {
+        let msg = "5:Hel";
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .try_read_netstring_alloc()
+            .await
+            .expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_fail_instead_of_allocating_announced_length() {
+        let msg = format!("{}:Hello", usize::MAX);
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .read_netstring_alloc()
+            .await
+            .expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_parse_netstring_longer_than_initial_buffer() {
+        let payload = vec![b'a'; 20_000];
+        let mut msg = b"20000:".to_vec();
+        msg.extend_from_slice(&payload);
+        msg.push(b',');
+
+        let mut test = Builder::new()
+            .read(&msg[..3_000])
+            .read(&msg[3_000..])
+            .build();
+
+        let res = test.read_netstring_alloc().await.expect("Test should pass");
+
+        assert_eq!(payload, res);
+    }
 }
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use tokio_netstring_trait::AsyncNetstringRead;
+    use std::io::ErrorKind;
+    use tokio_netstring_trait::{
+        AsyncNetstringRead, NetstringError, NetstringReadOptions, OversizePolicy,
+    };
     use std::time::Duration;
     use tokio_test::io::Builder;
 
@@ -107,4 +110,192 @@ mod tests {
             .await
             .expect_err("Message not finished");
     }
+
+    #[tokio::test]
+    async fn should_resume_netstring_after_buffer_too_small() {
+        let msg = "13:Hello, World!,";
+        let expected = "Hello, World!";
+        let mut buf = [0; 5];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .read_netstring(&mut buf)
+            .await
+            .expect_err("Buffer too small");
+
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        let too_small = err
+            .get_ref()
+            .and_then(|err| err.downcast_ref::<tokio_netstring_trait::BufferTooSmall>())
+            .expect("Test should pass");
+        assert_eq!(13, too_small.len);
+
+        let mut buf = vec![0; too_small.len];
+        let len = test
+            .resume_netstring(&mut buf, too_small.len)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(expected.as_bytes(), &buf[..len]);
+    }
+
+    #[tokio::test]
+    async fn should_skip_netstring_on_buffer_too_small() {
+        let msg = "13:Hello, World!,2:Hi,";
+        let options = NetstringReadOptions {
+            oversize: OversizePolicy::Skip,
+            ..Default::default()
+        };
+        let mut buf = [0; 5];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .read_netstring_with(&mut buf, options)
+            .await
+            .expect_err("Buffer too small");
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let len = test
+            .read_netstring_with(&mut buf, options)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(b"Hi", &buf[..len]);
+    }
+
+    #[tokio::test]
+    async fn should_truncate_netstring_on_buffer_too_small() {
+        let msg = "13:Hello, World!,2:Hi,";
+        let options = NetstringReadOptions {
+            oversize: OversizePolicy::Truncate,
+            ..Default::default()
+        };
+        let mut buf = [0; 5];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let len = test
+            .read_netstring_with(&mut buf, options)
+            .await
+            .expect("Test should pass");
+        assert_eq!(13, len);
+        assert_eq!(b"Hello", &buf);
+
+        let len = test
+            .read_netstring_with(&mut buf, options)
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(b"Hi", &buf[..len]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_buffer_too_small() {
+        let msg = "13:";
+        let options = NetstringReadOptions {
+            oversize: OversizePolicy::Fail,
+            ..Default::default()
+        };
+        let mut buf = [0; 5];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .read_netstring_with(&mut buf, options)
+            .await
+            .expect_err("Buffer too small");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_report_missing_bytes_on_incomplete_message() {
+        let msg = "13:Hello, World!";
+        let mut buf = [0; 13];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .read_netstring(&mut buf)
+            .await
+            .expect_err("Message not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(
+            Some(&NetstringError::EofInBody { missing: 1 }),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<NetstringError>())
+        );
+    }
+
+    #[tokio::test]
+    async fn should_report_eof_in_header() {
+        let msg = "13";
+        let mut buf = [0; 13];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .read_netstring(&mut buf)
+            .await
+            .expect_err("Header not finished");
+
+        assert_eq!(
+            Some(&NetstringError::EofInHeader),
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<NetstringError>())
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_none_on_eof_between_netstrings() {
+        let msg = "5:Hello,";
+        let mut buf = [0; 13];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let len = test
+            .try_read_netstring(&mut buf)
+            .await
+            .expect("Test should pass");
+        assert_eq!(Some(5), len);
+
+        let len = test
+            .try_read_netstring(&mut buf)
+            .await
+            .expect("Test should pass");
+        assert_eq!(None, len);
+    }
+
+    #[tokio::test]
+    async fn should_fail_try_read_on_eof_in_header() {
+        let msg = "13";
+        let mut buf = [0; 13];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .try_read_netstring(&mut buf)
+            .await
+            .expect_err("Header not finished");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[tokio::test]
+    async fn should_fail_try_read_on_eof_before_terminator() {
+        let msg = "5:Hello";
+        let mut buf = [0; 13];
+
+        let mut test = Builder::new().read(msg.as_bytes()).build();
+
+        let err = test
+            .try_read_netstring(&mut buf)
+            .await
+            .expect_err("Terminator missing");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
 }
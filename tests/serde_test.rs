@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use std::io::ErrorKind;
+    use tokio_netstring_trait::{
+        from_netstring, to_netstring, AsyncNetstringRead, AsyncNetstringWrite, NetstringError,
+        SerdeError,
+    };
+    use tokio_test::io::Builder;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Request {
+        method: String,
+        path: String,
+        length: u64,
+        keep_alive: bool,
+        tags: Vec<String>,
+        auth: Option<Auth>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Auth {
+        Anonymous,
+        Token(String),
+        Basic { user: String, password: String },
+    }
+
+    fn request() -> Request {
+        Request {
+            method: "GET".to_string(),
+            path: "/index.html".to_string(),
+            length: 0,
+            keep_alive: true,
+            tags: vec!["a".to_string(), "b".to_string()],
+            auth: Some(Auth::Token("secret".to_string())),
+        }
+    }
+
+    #[test]
+    fn should_serialize_scalars_as_text() {
+        assert_eq!(
+            b"2:13,",
+            &to_netstring(&13u8).expect("Test should pass")[..]
+        );
+        assert_eq!(
+            b"2:-1,",
+            &to_netstring(&-1i64).expect("Test should pass")[..]
+        );
+        assert_eq!(
+            b"3:1.5,",
+            &to_netstring(&1.5f64).expect("Test should pass")[..]
+        );
+        assert_eq!(
+            b"4:true,",
+            &to_netstring(&true).expect("Test should pass")[..]
+        );
+        assert_eq!(
+            b"5:Hello,",
+            &to_netstring("Hello").expect("Test should pass")[..]
+        );
+        assert_eq!(b"0:,", &to_netstring(&()).expect("Test should pass")[..]);
+    }
+
+    #[test]
+    fn should_serialize_sequences_as_lists() {
+        let netstring = to_netstring(&("Hello", 13, vec![true])).expect("Test should pass");
+
+        assert_eq!(b"23:5:Hello,2:13,7:4:true,,,", &netstring[..]);
+    }
+
+    #[test]
+    fn should_serialize_structs_as_lists_of_fields() {
+        let mut map = BTreeMap::new();
+        map.insert("a", 1);
+
+        assert_eq!(
+            b"8:1:a,1:1,,",
+            &to_netstring(&map).expect("Test should pass")[..]
+        );
+        assert_eq!(
+            b"12:9:Anonymous,,",
+            &to_netstring(&Auth::Anonymous).expect("Test should pass")[..]
+        );
+        assert_eq!(
+            b"3:0:,,",
+            &to_netstring(&Some(())).expect("Test should pass")[..]
+        );
+        assert_eq!(
+            b"0:,",
+            &to_netstring(&None::<()>).expect("Test should pass")[..]
+        );
+    }
+
+    #[test]
+    fn should_roundtrip_values() {
+        let netstring = to_netstring(&request()).expect("Test should pass");
+        assert_eq!(
+            request(),
+            from_netstring(&netstring).expect("Test should pass")
+        );
+
+        let auth = Auth::Basic {
+            user: "user".to_string(),
+            password: "pass".to_string(),
+        };
+        let netstring = to_netstring(&auth).expect("Test should pass");
+        assert_eq!(auth, from_netstring(&netstring).expect("Test should pass"));
+
+        let value = (Some(None::<u8>), 'x', vec![(1u128, -1.25f32)]);
+        let netstring = to_netstring(&value).expect("Test should pass");
+        assert_eq!(value, from_netstring(&netstring).expect("Test should pass"));
+    }
+
+    #[test]
+    fn should_borrow_strings() {
+        let value: (&str, &[u8]) =
+            from_netstring(b"13:5:Hello,2:\xff\xfe,,").expect("Test should pass");
+
+        assert_eq!(("Hello", &b"\xff\xfe"[..]), value);
+    }
+
+    #[test]
+    fn should_ignore_unknown_fields() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Method {
+            method: String,
+        }
+
+        let netstring = to_netstring(&request()).expect("Test should pass");
+        let method: Method = from_netstring(&netstring).expect("Test should pass");
+
+        assert_eq!("GET", method.method);
+    }
+
+    #[test]
+    fn should_fail_on_invalid_scalar() {
+        assert_eq!(
+            Err(SerdeError::InvalidScalar {
+                expected: "a decimal u8"
+            }),
+            from_netstring::<u8>(b"3:256,")
+        );
+        assert_eq!(
+            Err(SerdeError::InvalidScalar {
+                expected: "true or false"
+            }),
+            from_netstring::<bool>(b"3:yes,")
+        );
+    }
+
+    #[test]
+    fn should_fail_on_list_length_mismatch() {
+        assert_eq!(
+            Err(SerdeError::MissingItem),
+            from_netstring::<BTreeMap<String, u8>>(b"4:1:a,,")
+        );
+        assert_eq!(
+            Err(SerdeError::TrailingData),
+            from_netstring::<(u8,)>(b"8:1:1,1:2,,")
+        );
+        assert_eq!(
+            Err(SerdeError::TrailingData),
+            from_netstring::<u8>(b"1:1,1:2,")
+        );
+    }
+
+    #[test]
+    fn should_fail_on_invalid_framing() {
+        assert_eq!(
+            Err(SerdeError::Netstring(NetstringError::EofInBody {
+                missing: 1
+            })),
+            from_netstring::<Vec<u8>>(b"5:3:1,,")
+        );
+    }
+
+    #[tokio::test]
+    async fn should_write_and_read_serde() {
+        let mut buf = Vec::new();
+        buf.write_netstring_serde(&request())
+            .await
+            .expect("Test should pass");
+
+        let value: Request = (&buf[..])
+            .read_netstring_serde()
+            .await
+            .expect("Test should pass");
+
+        assert_eq!(request(), value);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_read_invalid_value() {
+        let mut test = Builder::new().read(b"3:abc,").build();
+
+        let err = test
+            .read_netstring_serde::<u32>()
+            .await
+            .expect_err("Invalid value");
+
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert!(err
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<SerdeError>())
+            .is_some());
+    }
+}
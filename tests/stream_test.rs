@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use futures::{SinkExt, StreamExt};
+    use std::io::ErrorKind;
+    use std::time::Duration;
+    use tokio_netstring_trait::{AsyncNetstringRead, NetstringReader, NetstringSink};
+    use tokio_test::io::Builder;
+
+    #[tokio::test]
+    async fn should_stream_netstrings() {
+        let msg = "13:Hello, World!,5:Hello,0:,";
+
+        let test = Builder::new().read(msg.as_bytes()).build();
+
+        let res: Vec<Vec<u8>> = test
+            .netstrings()
+            .map(|netstring| netstring.expect("Test should pass"))
+            .collect()
+            .await;
+
+        assert_eq!(
+            vec![b"Hello, World!".to_vec(), b"Hello".to_vec(), Vec::new()],
+            res
+        );
+    }
+
+    #[tokio::test]
+    async fn should_stream_netstrings_byte_by_byte() {
+        let msg = "13:Hello, World!,5:Hello,";
+
+        let mut test = Builder::new();
+
+        for i in 0..msg.len() {
+            test.read(&msg.as_bytes()[i..i + 1])
+                .wait(Duration::from_micros(5));
+        }
+
+        let res: Vec<Vec<u8>> = test
+            .build()
+            .netstrings()
+            .map(|netstring| netstring.expect("Test should pass"))
+            .collect()
+            .await;
+
+        assert_eq!(vec![b"Hello, World!".to_vec(), b"Hello".to_vec()], res);
+    }
+
+    #[tokio::test]
+    async fn should_end_stream_after_incomplete_message() {
+        let msg = "5:Hello,13:Hello";
+
+        let test = Builder::new().read(msg.as_bytes()).build();
+        let mut netstrings = test.netstrings();
+
+        let first = netstrings.next().await.expect("Netstring expected");
+        let second = netstrings.next().await.expect("Error expected");
+
+        assert_eq!(b"Hello".to_vec(), first.expect("Test should pass"));
+        assert_eq!(
+            ErrorKind::UnexpectedEof,
+            second.expect_err("Message not finished").kind()
+        );
+        assert!(netstrings.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn should_sink_netstrings() {
+        let expected = "13:Hello, World!,5:Hello,";
+
+        let test = Builder::new().write(expected.as_bytes()).build();
+        let mut sink = NetstringSink::new(test);
+
+        sink.feed("Hello, World!").await.expect("Test should pass");
+        sink.send(b"Hello").await.expect("Test should pass");
+    }
+
+    #[tokio::test]
+    async fn should_forward_stream_into_sink() {
+        let msg = "13:Hello, World!,5:Hello,";
+
+        let reader = Builder::new().read(msg.as_bytes()).build();
+        let writer = Builder::new().write(msg.as_bytes()).build();
+
+        reader
+            .netstrings()
+            .forward(NetstringSink::new(writer))
+            .await
+            .expect("Test should pass");
+    }
+
+    #[tokio::test]
+    async fn should_stream_netstrings_from_reader() {
+        let reader = Builder::new().read(b"5:Hello,0:,").build();
+
+        let frames: Vec<_> = NetstringReader::new(reader)
+            .map(|frame| frame.expect("Test should pass"))
+            .collect()
+            .await;
+
+        assert_eq!(vec![b"Hello".to_vec(), Vec::new()], frames);
+    }
+}
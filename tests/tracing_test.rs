@@ -0,0 +1,306 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::io::{Cursor, ErrorKind};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tokio_netstring_trait::{
+        AsyncNetstringRead, AsyncNetstringWrite, NetstringRead, NetstringWrite,
+    };
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// The fields of an event, together with the names of the spans it was emitted in.
+    #[derive(Debug)]
+    struct Captured {
+        fields: HashMap<String, String>,
+        spans: Vec<&'static str>,
+    }
+
+    #[derive(Default)]
+    struct FieldVisitor(HashMap<String, String>);
+
+    impl Visit for FieldVisitor {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    /// Captures the events of this crate and tracks the spans entered on the current thread.
+    #[derive(Clone, Default)]
+    struct CapturingSubscriber {
+        next_id: Arc<AtomicU64>,
+        names: Arc<Mutex<HashMap<u64, &'static str>>>,
+        stack: Arc<Mutex<Vec<u64>>>,
+        events: Arc<Mutex<Vec<Captured>>>,
+    }
+
+    impl CapturingSubscriber {
+        fn take_events(&self) -> Vec<Captured> {
+            std::mem::take(&mut *self.events.lock().expect("Lock not poisoned"))
+        }
+    }
+
+    impl Subscriber for CapturingSubscriber {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut names = self.names.lock().expect("Lock not poisoned");
+            names.insert(id, span.metadata().name());
+            Id::from_u64(id)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            if !event
+                .metadata()
+                .target()
+                .starts_with("tokio_netstring_trait")
+            {
+                return;
+            }
+
+            let mut visitor = FieldVisitor::default();
+            event.record(&mut visitor);
+            let names = self.names.lock().expect("Lock not poisoned");
+            let stack = self.stack.lock().expect("Lock not poisoned");
+            let spans = stack.iter().map(|id| names[id]).collect();
+            self.events
+                .lock()
+                .expect("Lock not poisoned")
+                .push(Captured {
+                    fields: visitor.0,
+                    spans,
+                });
+        }
+
+        fn enter(&self, span: &Id) {
+            let mut stack = self.stack.lock().expect("Lock not poisoned");
+            stack.push(span.into_u64());
+        }
+
+        fn exit(&self, span: &Id) {
+            let mut stack = self.stack.lock().expect("Lock not poisoned");
+            if let Some(pos) = stack.iter().rposition(|id| *id == span.into_u64()) {
+                stack.remove(pos);
+            }
+        }
+    }
+
+    fn field<'a>(event: &'a Captured, name: &str) -> &'a str {
+        event
+            .fields
+            .get(name)
+            .map(String::as_str)
+            .unwrap_or_else(|| panic!("field {} missing in {:?}", name, event))
+    }
+
+    #[tokio::test]
+    async fn should_emit_event_per_read_frame() {
+        let subscriber = CapturingSubscriber::default();
+        let _guard = tracing::subscriber::set_default(subscriber.clone());
+        let mut test = tokio_test::io::Builder::new()
+            .read(b"5:Hello,")
+            .read(b"0:,")
+            .build();
+
+        let connection = tracing::info_span!("connection");
+        let _entered = connection.enter();
+        test.read_netstring_alloc().await.expect("Test should pass");
+        test.read_netstring_alloc().await.expect("Test should pass");
+
+        let events = subscriber.take_events();
+        assert_eq!(2, events.len());
+        assert_eq!("read", field(&events[0], "direction"));
+        assert_eq!("5", field(&events[0], "len"));
+        assert_eq!("ok", field(&events[0], "outcome"));
+        assert!(events[0].fields.contains_key("elapsed"));
+        assert_eq!(vec!["connection", "netstring_frame"], events[0].spans);
+        assert_eq!("0", field(&events[1], "len"));
+    }
+
+    #[tokio::test]
+    async fn should_emit_event_per_write_and_drop_frame() {
+        let subscriber = CapturingSubscriber::default();
+        let _guard = tracing::subscriber::set_default(subscriber.clone());
+        let mut test = tokio_test::io::Builder::new()
+            .read(b"5:Hello,")
+            .write(b"6:World!,")
+            .write(b"1:a,")
+            .write(b"2:bc,")
+            .build();
+
+        test.drop_netstring().await.expect("Test should pass");
+        test.write_netstring(b"World!")
+            .await
+            .expect("Test should pass");
+        test.write_netstrings(vec!["a", "bc"])
+            .await
+            .expect("Test should pass");
+
+        let events = subscriber.take_events();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| (field(event, "direction"), field(event, "len")))
+            .collect();
+        assert_eq!(
+            vec![
+                ("drop", "5"),
+                ("write", "6"),
+                ("write", "1"),
+                ("write", "2")
+            ],
+            summary
+        );
+        assert!(events.iter().all(|event| field(event, "outcome") == "ok"));
+    }
+
+    #[tokio::test]
+    async fn should_emit_error_kind_on_failure() {
+        let subscriber = CapturingSubscriber::default();
+        let _guard = tracing::subscriber::set_default(subscriber.clone());
+        let mut test = tokio_test::io::Builder::new().read(b"5;").build();
+
+        let err = test
+            .read_netstring_alloc()
+            .await
+            .expect_err("Wrong separator");
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+
+        let events = subscriber.take_events();
+        assert_eq!(1, events.len());
+        assert_eq!("read", field(&events[0], "direction"));
+        assert_eq!("error", field(&events[0], "outcome"));
+        assert_eq!("InvalidData", field(&events[0], "error_kind"));
+        assert!(!events[0].fields.contains_key("len"));
+    }
+
+    #[test]
+    fn should_emit_events_for_blocking_frames() {
+        let subscriber = CapturingSubscriber::default();
+        let _guard = tracing::subscriber::set_default(subscriber.clone());
+
+        let mut buf = Vec::new();
+        NetstringWrite::write_netstring(&mut buf, b"Hello").expect("Test passes");
+        buf.extend_from_slice(b"3:a");
+        let mut test = Cursor::new(buf);
+        NetstringRead::read_netstring_alloc(&mut test).expect("Test should pass");
+        let err = NetstringRead::read_netstring_alloc(&mut test).expect_err("Early eof");
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        let res = NetstringRead::try_read_netstring_alloc(&mut test).expect("Test should pass");
+        assert_eq!(None, res);
+
+        let events = subscriber.take_events();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| (field(event, "direction"), field(event, "outcome")))
+            .collect();
+        assert_eq!(
+            vec![("write", "ok"), ("read", "ok"), ("read", "error")],
+            summary
+        );
+        assert_eq!("UnexpectedEof", field(&events[2], "error_kind"));
+        assert_eq!(vec!["netstring_frame"], events[0].spans);
+    }
+
+    #[tokio::test]
+    async fn should_emit_event_per_copied_and_body_frame() {
+        let subscriber = CapturingSubscriber::default();
+        let _guard = tracing::subscriber::set_default(subscriber.clone());
+        let mut test = tokio_test::io::Builder::new()
+            .read(b"2:Hi,")
+            .read(b"3:abc,")
+            .build();
+
+        let mut copied = Vec::new();
+        tokio_netstring_trait::copy_netstring(&mut test, &mut copied)
+            .await
+            .expect("Test should pass");
+        let mut body = test.read_netstring_body().await.expect("Test should pass");
+        tokio::io::copy(&mut body, &mut tokio::io::sink())
+            .await
+            .expect("Test should pass");
+
+        let events = subscriber.take_events();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| (field(event, "direction"), field(event, "len")))
+            .collect();
+        assert_eq!(vec![("copy", "2"), ("read", "3")], summary);
+        assert!(events
+            .iter()
+            .all(|event| event.spans == vec!["netstring_frame"]));
+    }
+
+    #[cfg(feature = "codec")]
+    #[test]
+    fn should_emit_event_per_codec_frame() {
+        use bytes::BytesMut;
+        use tokio_netstring_trait::NetstringCodec;
+        use tokio_util::codec::{Decoder, Encoder};
+
+        let subscriber = CapturingSubscriber::default();
+        let _guard = tracing::subscriber::set_default(subscriber.clone());
+        let mut codec = NetstringCodec::new_with_max_length(5);
+
+        let mut buf = BytesMut::from(&b"5:He"[..]);
+        assert_eq!(None, codec.decode(&mut buf).expect("Test should pass"));
+        buf.extend_from_slice(b"llo,");
+        codec.decode(&mut buf).expect("Test should pass");
+        assert_eq!(None, codec.decode(&mut buf).expect("Test should pass"));
+        codec
+            .encode(&b"Hi"[..], &mut buf)
+            .expect("Test should pass");
+        codec
+            .encode(&b"World!"[..], &mut buf)
+            .expect_err("Frame too large");
+
+        let events = subscriber.take_events();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| (field(event, "direction"), field(event, "outcome")))
+            .collect();
+        assert_eq!(
+            vec![("read", "ok"), ("write", "ok"), ("write", "error")],
+            summary
+        );
+        assert_eq!("5", field(&events[0], "len"));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn should_emit_event_per_sink_frame() {
+        use futures::SinkExt;
+
+        let subscriber = CapturingSubscriber::default();
+        let _guard = tracing::subscriber::set_default(subscriber.clone());
+        let test = tokio_test::io::Builder::new().write(b"2:Hi,0:,").build();
+
+        let mut sink = tokio_netstring_trait::NetstringSink::new(test);
+        sink.feed("Hi").await.expect("Test should pass");
+        sink.feed("").await.expect("Test should pass");
+        SinkExt::<&str>::flush(&mut sink)
+            .await
+            .expect("Test should pass");
+
+        let events = subscriber.take_events();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| (field(event, "direction"), field(event, "len")))
+            .collect();
+        assert_eq!(vec![("write", "2"), ("write", "0")], summary);
+    }
+}
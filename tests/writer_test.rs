@@ -1,8 +1,76 @@
 #[cfg(test)]
 mod tests {
-    use tokio_netstring_trait::AsyncNetstringWrite;
+    use std::io::{ErrorKind, IoSlice};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
     use std::time::Duration;
+    use tokio::io::AsyncWrite;
+    use tokio_netstring_trait::AsyncNetstringWrite;
     use tokio_test::io::Builder;
+
+    /// Records the calls to `poll_write`, `poll_write_vectored` and `poll_flush`, accepting at most `max`
+    /// bytes per call.
+    struct RecordingWriter {
+        written: Vec<u8>,
+        writes: usize,
+        flushes: usize,
+        max: usize,
+        vectored: bool,
+    }
+
+    impl RecordingWriter {
+        fn new(max: usize, vectored: bool) -> Self {
+            RecordingWriter {
+                written: Vec::new(),
+                writes: 0,
+                flushes: 0,
+                max,
+                vectored,
+            }
+        }
+    }
+
+    impl AsyncWrite for RecordingWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.poll_write_vectored(cx, &[IoSlice::new(buf)])
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            self.writes += 1;
+            let mut wrote = 0;
+            for buf in bufs {
+                let len = buf.len().min(self.max - wrote);
+                self.written.extend_from_slice(&buf[..len]);
+                wrote += len;
+            }
+            Poll::Ready(Ok(wrote))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.vectored
+        }
+
+        fn poll_flush(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.flushes += 1;
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
     #[tokio::test]
     async fn should_write_netstring() {
         let msg = "Hello, World!";
@@ -67,4 +135,286 @@ mod tests {
             .await
             .expect("Test passes");
     }
+
+    #[tokio::test]
+    async fn should_write_netstring_with_one_vectored_write() {
+        let msg = vec![b'x'; 4096];
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        test.write_netstring(&msg).await.expect("Test passes");
+
+        assert_eq!(1, test.writes);
+        assert_eq!(b"4096:", &test.written[..5]);
+        assert_eq!(&msg[..], &test.written[5..4101]);
+        assert_eq!(b",", &test.written[4101..]);
+    }
+
+    #[tokio::test]
+    async fn should_write_netstring_vectored_in_chunks() {
+        let msg = "Hello, World!";
+        let expected = "13:Hello, World!,";
+        let mut test = RecordingWriter::new(2, true);
+
+        test.write_netstring(msg.as_bytes())
+            .await
+            .expect("Test passes");
+
+        assert_eq!(9, test.writes);
+        assert_eq!(expected.as_bytes(), &test.written[..]);
+    }
+
+    #[tokio::test]
+    async fn should_coalesce_small_netstring_without_vectored_writes() {
+        let msg = "Hello, World!";
+        let expected = "13:Hello, World!,";
+        let mut test = RecordingWriter::new(usize::MAX, false);
+
+        test.write_netstring(msg.as_bytes())
+            .await
+            .expect("Test passes");
+
+        assert_eq!(1, test.writes);
+        assert_eq!(expected.as_bytes(), &test.written[..]);
+    }
+
+    #[tokio::test]
+    async fn should_write_large_netstring_without_vectored_writes() {
+        let msg = vec![b'x'; 4096];
+        let mut test = RecordingWriter::new(usize::MAX, false);
+
+        test.write_netstring(&msg).await.expect("Test passes");
+
+        assert_eq!(3, test.writes);
+        assert_eq!(4102, test.written.len());
+        assert_eq!(b",", &test.written[4101..]);
+    }
+
+    #[tokio::test]
+    async fn should_write_netstring_without_flush() {
+        let msg = "Hello, World!";
+        let expected = "13:Hello, World!,";
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        test.write_netstring_no_flush(msg.as_bytes())
+            .await
+            .expect("Test passes");
+
+        assert_eq!(0, test.flushes);
+        assert_eq!(expected.as_bytes(), &test.written[..]);
+    }
+
+    #[tokio::test]
+    async fn should_write_netstrings_with_one_flush() {
+        let msgs = vec!["Hello", "", "World!"];
+        let expected = "5:Hello,0:,6:World!,";
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        test.write_netstrings(msgs).await.expect("Test passes");
+
+        assert_eq!(3, test.writes);
+        assert_eq!(1, test.flushes);
+        assert_eq!(expected.as_bytes(), &test.written[..]);
+    }
+
+    #[tokio::test]
+    async fn should_write_netstrings_byte_by_byte() {
+        let msgs = [&b"Hello"[..], &b"World!"[..]];
+        let expected = "5:Hello,6:World!,";
+
+        let mut test = Builder::new();
+
+        for i in 0..expected.len() {
+            test.write(&expected.as_bytes()[i..i + 1])
+                .wait(Duration::from_millis(5));
+        }
+
+        test.build()
+            .write_netstrings(msgs.iter())
+            .await
+            .expect("Test passes");
+    }
+
+    #[tokio::test]
+    async fn should_write_netstring_parts_with_one_vectored_write() {
+        let expected = "13:Hello, World!,";
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        test.write_netstring_parts(&[b"Hello", b", ", b"World!"])
+            .await
+            .expect("Test passes");
+
+        assert_eq!(1, test.writes);
+        assert_eq!(1, test.flushes);
+        assert_eq!(expected.as_bytes(), &test.written[..]);
+    }
+
+    #[tokio::test]
+    async fn should_write_netstring_parts_byte_by_byte() {
+        let expected = "13:Hello, World!,";
+
+        let mut test = Builder::new();
+
+        for i in 0..expected.len() {
+            test.write(&expected.as_bytes()[i..i + 1])
+                .wait(Duration::from_millis(5));
+        }
+
+        test.build()
+            .write_netstring_parts(&[b"Hello", b"", b", ", b"World!"])
+            .await
+            .expect("Test passes");
+    }
+
+    #[tokio::test]
+    async fn should_write_netstring_from_many_io_slices() {
+        let parts = vec![IoSlice::new(b"ab"); 20];
+        let expected = format!("40:{},", "ab".repeat(20));
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        test.write_netstring_vectored(&parts)
+            .await
+            .expect("Test passes");
+
+        assert_eq!(2, test.writes);
+        assert_eq!(expected.as_bytes(), &test.written[..]);
+    }
+
+    #[tokio::test]
+    async fn should_write_netstring_from_reader() {
+        let msg = vec![b'x'; 20_000];
+        let mut test = RecordingWriter::new(1000, true);
+
+        test.write_netstring_from_reader(msg.len(), &mut &msg[..])
+            .await
+            .expect("Test passes");
+
+        assert_eq!(1, test.flushes);
+        assert_eq!(b"20000:", &test.written[..6]);
+        assert_eq!(&msg[..], &test.written[6..20_006]);
+        assert_eq!(b",", &test.written[20_006..]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_reader_shorter_than_len() {
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        let err = test
+            .write_netstring_from_reader(13, &mut &b"Hello"[..])
+            .await
+            .expect_err("Reader too short");
+
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(b"13:Hello", &test.written[..]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_reader_longer_than_len() {
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        let err = test
+            .write_netstring_from_reader(5, &mut &b"Hello, World!"[..])
+            .await
+            .expect_err("Reader too long");
+
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert_eq!(b"5:Hello", &test.written[..]);
+    }
+
+    #[cfg(feature = "fs")]
+    #[tokio::test]
+    async fn should_write_netstring_from_file() {
+        let path = std::env::temp_dir().join("tokio_netstring_write_from_file");
+        tokio::fs::write(&path, b"Hello, World!")
+            .await
+            .expect("Test passes");
+        let mut file = tokio::fs::File::open(&path).await.expect("Test passes");
+        let mut test = RecordingWriter::new(usize::MAX, true);
+
+        test.write_netstring_from_file(&mut file)
+            .await
+            .expect("Test passes");
+
+        assert_eq!(b"13:Hello, World!,", &test.written[..]);
+        tokio::fs::remove_file(&path).await.expect("Test passes");
+    }
+
+    #[cfg(feature = "fs")]
+    #[tokio::test]
+    async fn should_write_netstring_from_file_to_non_send_writer() {
+        let path = std::env::temp_dir().join("tokio_netstring_write_from_file_non_send");
+        tokio::fs::write(&path, b"Hello")
+            .await
+            .expect("Test passes");
+        let mut file = tokio::fs::File::open(&path).await.expect("Test passes");
+        let mut written = Vec::new();
+        let mut test: Box<dyn AsyncWrite + Unpin + '_> = Box::new(&mut written);
+
+        test.write_netstring_from_file(&mut file)
+            .await
+            .expect("Test passes");
+
+        drop(test);
+        assert_eq!(b"5:Hello,", &written[..]);
+        tokio::fs::remove_file(&path).await.expect("Test passes");
+    }
+
+    /// Yields its item again after it reported the end, like an iterator that is not fused.
+    struct Restarting {
+        ended: bool,
+    }
+
+    impl Iterator for Restarting {
+        type Item = &'static str;
+
+        fn next(&mut self) -> Option<&'static str> {
+            self.ended = !self.ended;
+            match self.ended {
+                true => Some("a"),
+                false => None,
+            }
+        }
+    }
+
+    /// Records the writes, but reports the first flush as pending.
+    struct PendingFlushWriter {
+        written: Vec<u8>,
+        pending: bool,
+    }
+
+    impl AsyncWrite for PendingFlushWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.written.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            if std::mem::take(&mut self.pending) {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn should_not_resume_netstrings_after_pending_flush() {
+        let mut test = PendingFlushWriter {
+            written: Vec::new(),
+            pending: true,
+        };
+
+        test.write_netstrings(Restarting { ended: false })
+            .await
+            .expect("Test passes");
+
+        assert_eq!(b"1:a,", &test.written[..]);
+    }
 }